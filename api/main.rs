@@ -0,0 +1,176 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::{bail, Context};
+use axum::extract::{Path, RawQuery, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use pokemon_filter::pokemon::{Pokemon, PokemonType};
+use pokemon_filter::{dataset, url_state};
+
+const USAGE: &str = "\
+Usage: pokefilter-api [--data PATH] [--host ADDR] [--port PORT]
+
+Serves the pokedex as JSON over HTTP.
+
+Options:
+      --data PATH      Pokedex .bin or CompletePokemon-style .csv to serve
+                       (default: the built-in dataset)
+      --host ADDR      Address to listen on (default: 127.0.0.1)
+      --port PORT      Port to listen on (default: 3000)
+  -h, --help           Print this help
+
+Endpoints:
+  GET /pokemon         Matching Pokémon. Takes the same filter parameters as
+                       the web build's URLs, e.g. ?has=Fire&gen=1-3&spe=90-255&sort=-speed,
+                       plus offset and limit (default 0 and 50, at most 1000).
+                       A single number matches exactly, e.g. spe=90. Unknown
+                       parameters and bad values get a 400 response
+  GET /pokemon/{id}    One Pokémon by ID, e.g. /pokemon/charizard-mega-x
+  GET /types           Types present in the dataset, in game order
+  GET /generations     Generations present in the dataset";
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 1000;
+
+struct Args {
+    data: Option<PathBuf>,
+    host: IpAddr,
+    port: u16,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<Self>> {
+        let mut data = None;
+        let mut host = IpAddr::from([127, 0, 0, 1]);
+        let mut port = 3000;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--data" => data = Some(PathBuf::from(args.next().context("--data needs a path")?)),
+                "--host" => {
+                    let value = args.next().context("--host needs an address")?;
+                    host = value.parse().with_context(|| format!("'{}' is not an IP address", value))?;
+                }
+                "--port" => {
+                    let value = args.next().context("--port needs a number")?;
+                    port = value.parse().with_context(|| format!("'{}' is not a port number", value))?;
+                }
+                _ => bail!("unexpected argument '{}'\n\n{}", arg, USAGE),
+            }
+        }
+
+        Ok(Some(Self { data, host, port }))
+    }
+}
+
+type Pokedex = Arc<Vec<Pokemon>>;
+
+/// One page of `GET /pokemon` results.
+#[derive(Serialize)]
+struct Page {
+    // Matches before paging
+    total: usize,
+    offset: usize,
+    limit: usize,
+    results: Vec<Pokemon>,
+}
+
+/// An error response body, `{"error": "..."}`.
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let Some(args) = Args::parse(std::env::args().skip(1))? else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    let pokedex = match &args.data {
+        Some(path) => {
+            let loaded = dataset::load_file(path)
+                .with_context(|| format!("Failed to load {}", path.display()))?;
+            for warning in loaded.warnings() {
+                eprintln!("{}", warning);
+            }
+            loaded.pokemon
+        }
+        None => dataset::default_pokedex().context("Failed to load the built-in dataset")?,
+    };
+    println!("Loaded {} Pokemon", pokedex.len());
+
+    let app = Router::new()
+        .route("/pokemon", get(list_pokemon))
+        .route("/pokemon/:id", get(get_pokemon))
+        .route("/types", get(list_types))
+        .route("/generations", get(list_generations))
+        .with_state(Arc::new(pokedex));
+
+    let listener = tokio::net::TcpListener::bind((args.host, args.port))
+        .await
+        .with_context(|| format!("Failed to listen on {}:{}", args.host, args.port))?;
+    println!("Listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn list_pokemon(
+    State(pokedex): State<Pokedex>,
+    RawQuery(query): RawQuery,
+) -> Result<Json<Page>, ApiError> {
+    let query = query.unwrap_or_default();
+    let (paging, filters): (Vec<_>, Vec<_>) = form_urlencoded::parse(query.as_bytes())
+        .partition(|(key, _)| key == "offset" || key == "limit");
+    let mut criteria = url_state::parse_query_pairs(filters)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))?;
+    criteria.resolve_excluded_pokemon(&pokedex);
+
+    let mut offset = 0;
+    let mut limit = DEFAULT_LIMIT;
+    for (key, value) in paging {
+        let target = if key == "offset" { &mut offset } else { &mut limit };
+        *target = value.parse().map_err(|_| {
+            ApiError(StatusCode::BAD_REQUEST, format!("{} must be a whole number, got '{}'", key, value))
+        })?;
+    }
+    let limit = limit.min(MAX_LIMIT);
+
+    let matches = criteria.apply(&pokedex);
+    let results = matches.iter().skip(offset).take(limit).cloned().collect();
+    Ok(Json(Page { total: matches.len(), offset, limit, results }))
+}
+
+async fn get_pokemon(
+    State(pokedex): State<Pokedex>,
+    Path(id): Path<String>,
+) -> Result<Json<Pokemon>, ApiError> {
+    pokedex.iter()
+        .find(|p| p.id == id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("no Pokemon with ID '{}'", id)))
+}
+
+async fn list_types(State(pokedex): State<Pokedex>) -> Json<Vec<PokemonType>> {
+    let types = PokemonType::ALL.into_iter()
+        .filter(|&t| pokedex.iter().any(|p| p.type1 == t || p.type2 == Some(t)))
+        .collect();
+    Json(types)
+}
+
+async fn list_generations(State(pokedex): State<Pokedex>) -> Json<Vec<u8>> {
+    let mut generations: Vec<u8> = pokedex.iter().map(|p| p.generation).collect();
+    generations.sort_unstable();
+    generations.dedup();
+    Json(generations)
+}
@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context};
+use pokemon_filter::pokemon::Pokemon;
+use pokemon_filter::{dataset, diff};
+
+const USAGE: &str = "\
+Usage: pokediff OLD NEW [--exit-code]
+
+Lists the Pokémon added and removed between two datasets and every field that
+changed, matching entries on name and form.
+
+Arguments:
+  OLD                  Pokedex .bin or CompletePokemon-style .csv to compare from
+  NEW                  Pokedex .bin or CompletePokemon-style .csv to compare to
+
+Options:
+      --exit-code      Exit with status 1 if the datasets differ
+  -h, --help           Print this help";
+
+struct Args {
+    old: PathBuf,
+    new: PathBuf,
+    exit_code: bool,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item = String>) -> anyhow::Result<Option<Self>> {
+        let mut paths = Vec::new();
+        let mut exit_code = false;
+
+        for arg in args {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--exit-code" => exit_code = true,
+                _ if arg.starts_with('-') => bail!("unknown option '{}'\n\n{}", arg, USAGE),
+                _ => paths.push(PathBuf::from(arg)),
+            }
+        }
+
+        let [old, new]: [PathBuf; 2] = paths.try_into()
+            .map_err(|_| anyhow::anyhow!("expected two datasets to compare\n\n{}", USAGE))?;
+        Ok(Some(Self { old, new, exit_code }))
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let Some(args) = Args::parse(std::env::args().skip(1))? else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    let old = load(&args.old)?;
+    let new = load(&args.new)?;
+    let diff = diff::diff_datasets(&old, &new);
+
+    println!("{}: {} Pokemon", args.old.display(), old.len());
+    println!("{}: {} Pokemon", args.new.display(), new.len());
+
+    if diff.is_empty() {
+        println!("No differences");
+        return Ok(());
+    }
+
+    print_section("Added", diff.added.iter().map(|p| format!("+ {}", p.display_name())));
+    print_section("Removed", diff.removed.iter().map(|p| format!("- {}", p.display_name())));
+    print_section("Changed", diff.changed.iter().map(|c| c.to_string()));
+
+    if args.exit_code {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> anyhow::Result<Vec<Pokemon>> {
+    let loaded = dataset::load_file(path).with_context(|| format!("Failed to load {}", path.display()))?;
+    for warning in loaded.warnings() {
+        eprintln!("{}: {}", path.display(), warning);
+    }
+    Ok(loaded.pokemon)
+}
+
+fn print_section(title: &str, lines: impl ExactSizeIterator<Item = String>) {
+    if lines.len() == 0 {
+        return;
+    }
+    println!("\n{} ({}):", title, lines.len());
+    for line in lines {
+        println!("  {}", line);
+    }
+}
@@ -0,0 +1,261 @@
+use std::path::PathBuf;
+use anyhow::{anyhow, bail, Context};
+use pokemon_filter::export::{self, ExportFormat};
+use pokemon_filter::pokemon::Pokemon;
+use pokemon_filter::sort::{SortColumn, SortDirection, SortKey};
+use pokemon_filter::{dataset, query};
+
+const USAGE: &str = "\
+Usage: pokefilter [OPTIONS] [QUERY]...
+
+Filters the pokedex from the command line with the same rules as the app and
+prints the matches.
+
+Arguments:
+  QUERY                Search terms as typed into the app's search box,
+                       e.g. 'type:fire gen:1-3 spe>=90 -type:flying'
+
+Options:
+      --data PATH      Pokedex .bin or CompletePokemon-style .csv to read
+                       (default: the built-in dataset)
+      --name TEXT      Name contains TEXT, or matches it if it has a '*'
+      --type TYPE      Has TYPE in either slot, can be given more than once
+      --type1 TYPE     Primary type is TYPE
+      --type2 TYPE     Secondary type is TYPE
+      --exclude TYPE   Has neither slot of TYPE, can be given more than once
+      --weak TYPE      Takes super effective damage from TYPE
+      --resists TYPE   Takes not very effective damage from TYPE
+      --immune TYPE    Takes no damage from TYPE
+      --gen RANGE      Generation, e.g. 4 or 1-3
+      --form KINDS     Form kind is one of KINDS, e.g. base or mega,regional.
+                       Kinds are base, mega, alolan, galarian, hisuian,
+                       paldean, regional, gigantamax, gender and alternate
+      --exclude-form KINDS
+                       Form kind is none of KINDS
+      --min-STAT N     Lowest allowed STAT, one of hp, attack, defense,
+      --max-STAT N     sp-atk, sp-def, speed, total, height or weight
+      --sort COLUMN    Sort by COLUMN, optionally COLUMN:asc or COLUMN:desc.
+                       Can be given more than once, earlier keys win ties
+      --limit N        Print at most N Pokémon
+      --format FORMAT  table, json, csv or markdown (default: table)
+  -h, --help           Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Table,
+    Export(ExportFormat),
+}
+
+struct Args {
+    data: Option<PathBuf>,
+    // Each filter as the flag it came from and the equivalent query term
+    terms: Vec<(String, String)>,
+    sort: Vec<SortKey>,
+    limit: Option<usize>,
+    format: OutputFormat,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<Self>> {
+        let mut data = None;
+        let mut terms = Vec::new();
+        let mut sort = Vec::new();
+        let mut limit = None;
+        let mut format = OutputFormat::Table;
+
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Ok(None);
+            }
+            if !arg.starts_with("--") {
+                terms.push((arg.clone(), arg));
+                continue;
+            }
+            let value = args.next().with_context(|| format!("{} needs a value", arg))?;
+            let term = match arg.as_str() {
+                "--data" => {
+                    data = Some(PathBuf::from(value));
+                    continue;
+                }
+                "--sort" => {
+                    sort.push(parse_sort_key(&value)?);
+                    continue;
+                }
+                "--limit" => {
+                    limit = Some(value.parse().map_err(|_| anyhow!("--limit needs a whole number, got '{}'", value))?);
+                    continue;
+                }
+                "--format" => {
+                    format = parse_format(&value)?;
+                    continue;
+                }
+                "--name" => format!("name:{}", quote(&value)),
+                "--type" => format!("type:{}", value),
+                "--type1" => format!("type1:{}", value),
+                "--type2" => format!("type2:{}", value),
+                "--exclude" => format!("-type:{}", value),
+                "--weak" => format!("weak:{}", value),
+                "--resists" => format!("resists:{}", value),
+                "--immune" => format!("immune:{}", value),
+                "--gen" => format!("gen:{}", value),
+                "--form" => format!("form:{}", value),
+                "--exclude-form" => format!("-form:{}", value),
+                _ => match range_term(&arg, &value) {
+                    Some(term) => term,
+                    None => bail!("unknown option '{}'\n\n{}", arg, USAGE),
+                },
+            };
+            terms.push((format!("{} {}", arg, value), term));
+        }
+
+        Ok(Some(Self { data, terms, sort, limit, format }))
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let Some(args) = Args::parse(std::env::args().skip(1))? else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    let pokedex = match &args.data {
+        Some(path) => {
+            let loaded = dataset::load_file(path)
+                .with_context(|| format!("Failed to load {}", path.display()))?;
+            // On stderr so they don't mix with the results
+            for warning in loaded.warnings() {
+                eprintln!("{}", warning);
+            }
+            loaded.pokemon
+        }
+        None => dataset::default_pokedex().context("Failed to load the built-in dataset")?,
+    };
+
+    // Check each term on its own first so an error names the flag it came from
+    for (source, term) in &args.terms {
+        query::parse_query(term).map_err(|e| anyhow!("{}: {}", source, e.message))?;
+    }
+    let terms: Vec<&str> = args.terms.iter().map(|(_, term)| term.as_str()).collect();
+    let mut criteria = query::parse_query(&terms.join(" ")).map_err(|e| anyhow!("{}", e))?;
+    criteria.sort = args.sort;
+
+    let mut matches = criteria.apply(&pokedex);
+    if let Some(limit) = args.limit {
+        matches.truncate(limit);
+    }
+
+    match args.format {
+        OutputFormat::Table => print!("{}", to_table(&matches)),
+        OutputFormat::Export(format) => println!("{}", export::export(&matches, format)?.trim_end()),
+    }
+    Ok(())
+}
+
+// "--min-speed 90" -> "spe>=90"
+fn range_term(flag: &str, value: &str) -> Option<String> {
+    let (op, stat) = if let Some(stat) = flag.strip_prefix("--min-") {
+        (">=", stat)
+    } else {
+        ("<=", flag.strip_prefix("--max-")?)
+    };
+    let key = match stat {
+        "hp" => "hp",
+        "attack" | "atk" => "atk",
+        "defense" | "def" => "def",
+        "sp-atk" | "spa" => "spa",
+        "sp-def" | "spd" => "spd",
+        "speed" | "spe" => "spe",
+        "total" | "bst" => "bst",
+        "height" => "height",
+        "weight" => "weight",
+        _ => return None,
+    };
+    Some(format!("{}{}{}", key, op, value))
+}
+
+// "speed", "speed:asc" or "speed:desc"
+fn parse_sort_key(value: &str) -> anyhow::Result<SortKey> {
+    let (column, direction) = value.split_once(':').unwrap_or((value, ""));
+    let column: SortColumn = column.parse().map_err(|e| anyhow!("--sort {}: {}", value, e))?;
+    let direction = match direction.to_lowercase().as_str() {
+        "" => column.default_direction(),
+        "asc" => SortDirection::Ascending,
+        "desc" => SortDirection::Descending,
+        other => bail!("--sort {}: direction must be asc or desc, got '{}'", value, other),
+    };
+    Ok(SortKey { column, direction })
+}
+
+fn parse_format(value: &str) -> anyhow::Result<OutputFormat> {
+    if value.eq_ignore_ascii_case("table") {
+        return Ok(OutputFormat::Table);
+    }
+    ExportFormat::ALL.iter()
+        .find(|f| f.extension() == value.to_lowercase() || f.name().eq_ignore_ascii_case(value))
+        .map(|&f| OutputFormat::Export(f))
+        .ok_or_else(|| anyhow!("--format must be table, json, csv or markdown, got '{}'", value))
+}
+
+// Keeps a value with spaces together as one query term
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+// Plain text columns padded to line up, for reading in a terminal
+fn to_table(pokemon: &[Pokemon]) -> String {
+    let header = ["Name", "Type", "HP", "Atk", "Def", "SpA", "SpD", "Spe", "Total", "Height", "Weight", "Gen"];
+    let rows: Vec<[String; 12]> = pokemon.iter()
+        .map(|p| [
+            p.display_name(),
+            match p.type2 {
+                Some(type2) => format!("{}/{}", p.type1, type2),
+                None => p.type1.to_string(),
+            },
+            p.hp.to_string(),
+            p.attack.to_string(),
+            p.defense.to_string(),
+            p.sp_atk.to_string(),
+            p.sp_def.to_string(),
+            p.speed.to_string(),
+            p.total.to_string(),
+            format!("{} m", p.height),
+            format!("{} kg", p.weight),
+            p.generation.to_string(),
+        ])
+        .collect();
+
+    let mut widths = header.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut push_row = |cells: &[String]| {
+        let line: Vec<String> = cells.iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                // Name and type read left to right, numbers line up on the right
+                if i < 2 {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    };
+    push_row(&header.map(String::from));
+    for row in &rows {
+        push_row(row);
+    }
+    out.push_str(&format!("{} Pokémon\n", rows.len()));
+    out
+}
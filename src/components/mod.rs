@@ -1,9 +1,6 @@
 //! The components module contains all shared components for our app. Components are the building blocks of dioxus apps.
 //! They can be used to defined common UI elements like buttons, forms, and modals. In this template, we define a Hero
-//! component and an Echo component for fullstack apps to be used in our app.
+//! component to be used in our app. Server functions live in the library's `server` module.
 
 mod hero;
 pub use hero::Hero;
-
-mod echo;
-pub use echo::Echo;
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+use csv::StringRecord;
+use crate::dataset;
+use crate::pokemon::{self, FormKind, ParseTypeError, Pokemon, PokemonType};
+
+/// Header row of CompletePokemon.csv, in column order.
+pub const HEADERS: [&str; 15] = [
+    "Number", "Generation", "Name", "Form", "Type1", "Type2", "Total", "HP",
+    "Attack", "Defense", "Sp. Atk", "Sp. Def", "Speed", "Height", "Weight",
+];
+
+/// Where each field lives in a particular file. Columns are found by header
+/// name, falling back to the CompletePokemon.csv position (without the Number
+/// column if the file doesn't have one, as in older drops).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Columns {
+    // Only found by name, older drops of the CSV don't have it
+    pub number: Option<usize>,
+    pub generation: usize,
+    pub name: usize,
+    pub form: usize,
+    pub type1: usize,
+    pub type2: usize,
+    pub total: usize,
+    pub hp: usize,
+    pub attack: usize,
+    pub defense: usize,
+    pub sp_atk: usize,
+    pub sp_def: usize,
+    pub speed: usize,
+    pub height: usize,
+    pub weight: usize,
+}
+
+impl Columns {
+    pub fn from_headers(headers: &StringRecord) -> Self {
+        let position = |i: usize| headers.iter().position(|h| h == HEADERS[i]);
+        let number = position(0);
+        let offset = if number.is_some() { 0 } else { 1 };
+        let find = |i: usize| position(i).unwrap_or(i - offset);
+        Self {
+            number,
+            generation: find(1),
+            name: find(2),
+            form: find(3),
+            type1: find(4),
+            type2: find(5),
+            total: find(6),
+            hp: find(7),
+            attack: find(8),
+            defense: find(9),
+            sp_atk: find(10),
+            sp_def: find(11),
+            speed: find(12),
+            height: find(13),
+            weight: find(14),
+        }
+    }
+}
+
+/// Result of reading a CSV: the parsed Pokémon plus the rows that had to be skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvImport {
+    pub pokemon: Vec<Pokemon>,
+    pub skipped: Vec<RowError>,
+}
+
+/// A row that couldn't be parsed, with its 1-based line number in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    pub line: u64,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Csv(csv::Error),
+    // A misspelled type is a data bug, not a row we can quietly skip
+    UnknownType { line: u64, error: ParseTypeError },
+    NoRows,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(e) => write!(f, "failed to read CSV: {}", e),
+            CsvError::UnknownType { line, error } => write!(f, "line {}: {}", line, error),
+            CsvError::NoRows => write!(
+                f,
+                "no Pokemon were loaded from the CSV, check that it matches the CompletePokemon.csv layout"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Parses a CSV in the CompletePokemon.csv layout. Rows with bad numbers are
+/// skipped and reported, unknown types and files without any rows are errors.
+/// Rows without a dex number, e.g. from files without a Number column, are looked
+/// up with `dataset::fill_dex_numbers`.
+pub fn parse_csv(bytes: &[u8]) -> Result<CsvImport, CsvError> {
+    let mut rdr = csv::Reader::from_reader(bytes);
+    let headers = rdr.headers().map_err(CsvError::Csv)?.clone();
+    let columns = Columns::from_headers(&headers);
+
+    let mut pokemon = Vec::new();
+    let mut skipped = Vec::new();
+
+    for result in rdr.records() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                skipped.push(RowError { line, message: e.to_string() });
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line());
+
+        match parse_pokemon_from_record(&record, &columns) {
+            Ok(p) => pokemon.push(p),
+            Err(e) => match e.downcast::<ParseTypeError>() {
+                Ok(error) => return Err(CsvError::UnknownType { line, error: *error }),
+                Err(e) => skipped.push(RowError { line, message: e.to_string() }),
+            },
+        }
+    }
+
+    if pokemon.is_empty() {
+        return Err(CsvError::NoRows);
+    }
+    dataset::fill_dex_numbers(&mut pokemon);
+    pokemon::assign_base_forms(&mut pokemon);
+    Ok(CsvImport { pokemon, skipped })
+}
+
+// Helper function to parse a record into a Pokemon
+pub fn parse_pokemon_from_record(
+    record: &StringRecord,
+    columns: &Columns,
+) -> Result<Pokemon, Box<dyn std::error::Error>> {
+    // Looked up afterwards by `parse_csv` when the file has no Number column
+    let dex_number = match columns.number {
+        Some(i) => number(record, i, HEADERS[0])?,
+        None => 0,
+    };
+    let generation = number(record, columns.generation, HEADERS[1])?;
+    let name = record.get(columns.name).unwrap_or("").to_string();
+
+    let form_str = record.get(columns.form).unwrap_or("").trim();
+    let form = if form_str.is_empty() { None } else { Some(form_str.to_string()) };
+
+    let type1 = record.get(columns.type1).unwrap_or("").parse::<PokemonType>()?;
+
+    let type2_str = record.get(columns.type2).unwrap_or("").trim();
+    let type2 = if type2_str.is_empty() { None } else { Some(type2_str.parse::<PokemonType>()?) };
+
+    let total = number(record, columns.total, HEADERS[6])?;
+    let hp = number(record, columns.hp, HEADERS[7])?;
+    let attack = number(record, columns.attack, HEADERS[8])?;
+    let defense = number(record, columns.defense, HEADERS[9])?;
+    let sp_atk = number(record, columns.sp_atk, HEADERS[10])?;
+    let sp_def = number(record, columns.sp_def, HEADERS[11])?;
+    let speed = number(record, columns.speed, HEADERS[12])?;
+    let height = number(record, columns.height, HEADERS[13])?;
+    let weight = number(record, columns.weight, HEADERS[14])?;
+
+    Ok(Pokemon {
+        id: Pokemon::make_id(&name, form.as_deref()),
+        // Refined by `parse_csv` once every row is known
+        form_kind: FormKind::classify(form.as_deref()),
+        dex_number, generation, name, form, type1, type2, total, hp, attack,
+        defense, sp_atk, sp_def, speed, height, weight
+    })
+}
+
+// A numeric field, with an error naming the column, e.g. "Speed 'abc' is not a valid number"
+fn number<T: FromStr>(record: &StringRecord, index: usize, header: &str) -> Result<T, String> {
+    let text = record.get(index).unwrap_or("0");
+    text.parse().map_err(|_| format!("{} '{}' is not a valid number", header, text))
+}
@@ -0,0 +1,771 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use bincode::{config, decode_from_slice, encode_to_vec, Decode, Encode};
+use sha2::{Digest, Sha256};
+use crate::csv_import::{self, CsvError, RowError};
+use crate::pokemon::{self, FormKind, Pokemon, PokemonType};
+
+/// First bytes of every pokedex file.
+pub const MAGIC: [u8; 4] = *b"PKDX";
+
+/// Version of the `Pokemon` record layout. Bump it whenever a field of `Pokemon`
+/// changes, and teach `decode_pokedex` how to migrate the previous version.
+pub const SCHEMA_VERSION: u16 = 3;
+
+/// The default dataset, compiled into the binary so the app works wherever it is started.
+/// The web build fetches it from the server instead, see `fetch_pokedex`.
+#[cfg(not(target_arch = "wasm32"))]
+pub const DEFAULT_POKEDEX: &[u8] = include_bytes!("../assets/pokedex_default.bin");
+
+/// A pokedex file chosen by the user to load instead of the embedded default.
+/// Provided to `App` as root context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetPath(pub PathBuf);
+
+/// The Pokémon the app is showing and a label for where they came from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dataset {
+    pub source: String,
+    pub pokemon: Vec<Pokemon>,
+    // Problems met while loading it, for the frontend to show or log
+    pub warnings: Vec<String>,
+}
+
+impl Dataset {
+    /// Loads the user's file if one was given, otherwise (or if it fails) the
+    /// embedded default.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_startup(path: Option<&Path>) -> Self {
+        let mut warnings = Vec::new();
+        if let Some(path) = path {
+            match load_file(path) {
+                Ok(loaded) => {
+                    return Dataset {
+                        source: file_label(&path.to_string_lossy()),
+                        warnings: loaded.warnings(),
+                        pokemon: loaded.pokemon,
+                    };
+                }
+                Err(e) => warnings.push(format!("Failed to load {}: {}, using the built-in dataset", path.display(), e)),
+            }
+        }
+        let pokemon = default_pokedex().unwrap_or_else(|e| {
+            warnings.push(format!("Failed to load the built-in dataset: {}", e));
+            Vec::new()
+        });
+        Dataset { source: "Built-in dataset".to_string(), pokemon, warnings }
+    }
+
+    /// The web build has no filesystem or embedded data, so it starts empty
+    /// until `fetch_pokedex` returns.
+    #[cfg(target_arch = "wasm32")]
+    pub fn load_startup(_path: Option<&Path>) -> Self {
+        Dataset { source: "Built-in dataset".to_string(), pokemon: Vec::new(), warnings: Vec::new() }
+    }
+
+    /// Every type that appears in either slot, in `PokemonType` order.
+    pub fn types(&self) -> Vec<PokemonType> {
+        let mut types = Vec::new();
+        for pokemon in &self.pokemon {
+            if !types.contains(&pokemon.type1) {
+                types.push(pokemon.type1);
+            }
+            if let Some(type2) = pokemon.type2 {
+                if !types.contains(&type2) {
+                    types.push(type2);
+                }
+            }
+        }
+        types.sort();
+        types
+    }
+
+    /// The tallest height, rounded up to a whole metre for the slider.
+    pub fn max_height(&self) -> f32 {
+        self.pokemon.iter().map(|p| p.height).fold(0.0, f32::max).ceil()
+    }
+
+    /// The heaviest weight, rounded up to a whole kilogram for the slider.
+    pub fn max_weight(&self) -> f32 {
+        self.pokemon.iter().map(|p| p.weight).fold(0.0, f32::max).ceil()
+    }
+}
+
+/// Pokémon read from a file, along with what the caller may want to warn about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedPokedex {
+    pub pokemon: Vec<Pokemon>,
+    // CSV rows that couldn't be parsed and were left out
+    pub skipped: Vec<RowError>,
+    // Display names of entries without a dex number, see `unknown_dex_numbers`
+    pub unknown_dex_numbers: Vec<String>,
+}
+
+impl LoadedPokedex {
+    /// One line per problem, e.g. "Skipped line 7: Speed 'abc' is not a valid number".
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = self.skipped.iter()
+            .map(|error| format!("Skipped {}", error))
+            .collect();
+        if !self.unknown_dex_numbers.is_empty() {
+            warnings.push(format!(
+                "No dex number for {} Pokemon: {}",
+                self.unknown_dex_numbers.len(),
+                self.unknown_dex_numbers.join(", ")
+            ));
+        }
+        warnings
+    }
+}
+
+/// Just the file name of a path, for showing where a dataset came from.
+pub fn file_label(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+/// Fixed header written in front of the bincode-encoded `Vec<Pokemon>`.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+pub struct PokedexHeader {
+    pub magic: [u8; 4],
+    pub version: u16,
+    pub record_count: u32,
+    // SHA-256 of the CSV the data was converted from
+    pub source_hash: [u8; 32],
+    // CRC-32 of the payload following the header
+    pub crc: u32,
+}
+
+impl PokedexHeader {
+    pub fn source_hash_hex(&self) -> String {
+        self.source_hash.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Everything that can make a pokedex file unreadable.
+#[derive(Debug)]
+pub enum PokedexError {
+    Io(std::io::Error),
+    // The web build couldn't download the file
+    Fetch(String),
+    Csv(CsvError),
+    // Neither a versioned file nor a headerless file from before versioning
+    NotAPokedex,
+    // A headerless file from the first release, which stored types as text,
+    // naming a type that no longer exists. `record` is 0-based.
+    LegacyType { record: usize, name: String },
+    UnsupportedVersion { found: u16, newest: u16 },
+    ChecksumMismatch { expected: u32, found: u32 },
+    CountMismatch { expected: u32, found: usize },
+    Decode(bincode::error::DecodeError),
+    Encode(bincode::error::EncodeError),
+}
+
+impl fmt::Display for PokedexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PokedexError::Io(e) => write!(f, "failed to read pokedex file: {}", e),
+            PokedexError::Fetch(e) => write!(f, "failed to download pokedex file: {}", e),
+            PokedexError::Csv(e) => write!(f, "{}", e),
+            PokedexError::NotAPokedex => write!(
+                f,
+                "not a pokedex file: it has no PKDX header and doesn't decode as a headerless file from an older version"
+            ),
+            PokedexError::LegacyType { record, name } => write!(
+                f,
+                "old pokedex file can't be migrated: record {} has unknown type '{}'",
+                record, name
+            ),
+            PokedexError::UnsupportedVersion { found, newest } if found > newest => write!(
+                f,
+                "pokedex schema version {} is newer than this build understands (up to {}), update the app",
+                found, newest
+            ),
+            PokedexError::UnsupportedVersion { found, .. } => write!(
+                f,
+                "pokedex schema version {} is too old to migrate, re-run convert on the source CSV",
+                found
+            ),
+            PokedexError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "pokedex data is corrupt: CRC is {:08x} but the header says {:08x}",
+                found, expected
+            ),
+            PokedexError::CountMismatch { expected, found } => write!(
+                f,
+                "pokedex data is truncated or corrupt: header says {} records but {} were decoded",
+                expected, found
+            ),
+            PokedexError::Decode(e) => write!(f, "failed to decode pokedex records: {}", e),
+            PokedexError::Encode(e) => write!(f, "failed to encode pokedex records: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PokedexError {}
+
+/// Reads a pokedex file, or a raw CSV in the CompletePokemon.csv layout, from disk.
+pub fn load_file(path: &Path) -> Result<LoadedPokedex, PokedexError> {
+    let bytes = fs::read(path).map_err(PokedexError::Io)?;
+    load_bytes(&path.to_string_lossy(), &bytes)
+}
+
+/// Decodes file contents, treating names ending in `.csv` as CSV and anything
+/// else as a pokedex binary.
+pub fn load_bytes(file_name: &str, bytes: &[u8]) -> Result<LoadedPokedex, PokedexError> {
+    let (pokemon, skipped) = if file_name.to_lowercase().ends_with(".csv") {
+        let import = csv_import::parse_csv(bytes).map_err(PokedexError::Csv)?;
+        (import.pokemon, import.skipped)
+    } else {
+        (decode_pokedex(bytes)?, Vec::new())
+    };
+    let unknown_dex_numbers = unknown_dex_numbers(&pokemon);
+    Ok(LoadedPokedex { pokemon, skipped, unknown_dex_numbers })
+}
+
+/// Display names of the entries whose dex number couldn't be looked up, see
+/// `fill_dex_numbers`.
+pub fn unknown_dex_numbers(pokemon: &[Pokemon]) -> Vec<String> {
+    pokemon.iter()
+        .filter(|p| p.dex_number == 0)
+        .map(|p| p.display_name())
+        .collect()
+}
+
+/// The embedded default dataset.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_pokedex() -> Result<Vec<Pokemon>, PokedexError> {
+    decode_pokedex(DEFAULT_POKEDEX)
+}
+
+/// Downloads and decodes a pokedex file served alongside the web build.
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_pokedex(url: &str) -> Result<Vec<Pokemon>, PokedexError> {
+    let response = gloo_net::http::Request::get(url)
+        .send()
+        .await
+        .map_err(|e| PokedexError::Fetch(e.to_string()))?;
+    if !response.ok() {
+        return Err(PokedexError::Fetch(format!("{} returned HTTP {}", url, response.status())));
+    }
+    let bytes = response.binary().await.map_err(|e| PokedexError::Fetch(e.to_string()))?;
+    decode_pokedex(&bytes)
+}
+
+/// Looks up `dex_number` for entries that don't have one, from data that
+/// predates the Number column. An entry gets the number of the built-in entry
+/// with the same ID, or failing that of the built-in species with the same name
+/// (unless the name is shared by several species, like Nidoran). Anything else
+/// keeps 0 for unknown. Returns how many entries are still unknown.
+pub fn fill_dex_numbers(pokemon: &mut [Pokemon]) -> usize {
+    let reference = reference_dex_numbers();
+    for p in pokemon.iter_mut().filter(|p| p.dex_number == 0) {
+        let by_name = || reference.by_name.get(&p.name).copied().flatten();
+        p.dex_number = reference.by_id.get(&p.id).copied().or_else(by_name).unwrap_or(0);
+    }
+    pokemon.iter().filter(|p| p.dex_number == 0).count()
+}
+
+#[derive(Default)]
+struct DexNumbers {
+    by_id: HashMap<String, u16>,
+    // `None` for names that appear under more than one number
+    by_name: HashMap<String, Option<u16>>,
+}
+
+// Built once from the embedded default dataset. Decoded without migration, so
+// a stale embedded file gives an empty table rather than recursing into here.
+fn reference_dex_numbers() -> &'static DexNumbers {
+    static NUMBERS: OnceLock<DexNumbers> = OnceLock::new();
+    NUMBERS.get_or_init(|| {
+        let mut numbers = DexNumbers::default();
+        for p in embedded_current_pokedex() {
+            numbers.by_id.insert(p.id.clone(), p.dex_number);
+            let entry = numbers.by_name.entry(p.name.clone()).or_insert(Some(p.dex_number));
+            if *entry != Some(p.dex_number) {
+                *entry = None;
+            }
+        }
+        numbers
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn embedded_current_pokedex() -> Vec<Pokemon> {
+    match read_header(DEFAULT_POKEDEX) {
+        Ok(Some((header, len))) if header.version == SCHEMA_VERSION => {
+            decode_records(&DEFAULT_POKEDEX[len..]).unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
+// The web build has no embedded dataset to look numbers up in
+#[cfg(target_arch = "wasm32")]
+fn embedded_current_pokedex() -> Vec<Pokemon> {
+    Vec::new()
+}
+
+/// Hash identifying the CSV a pokedex was converted from.
+pub fn hash_source(csv: &[u8]) -> [u8; 32] {
+    Sha256::digest(csv).into()
+}
+
+/// Encodes the records behind a header for the current schema version.
+pub fn encode_pokedex(pokemon: &[Pokemon], source_hash: [u8; 32]) -> Result<Vec<u8>, PokedexError> {
+    let payload = encode_to_vec(pokemon, config::standard()).map_err(PokedexError::Encode)?;
+    let header = PokedexHeader {
+        magic: MAGIC,
+        version: SCHEMA_VERSION,
+        record_count: pokemon.len() as u32,
+        source_hash,
+        crc: crc32fast::hash(&payload),
+    };
+    let mut bytes = encode_to_vec(&header, config::standard()).map_err(PokedexError::Encode)?;
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// Reads just the header, or `None` for a headerless file from before versioning.
+pub fn read_header(bytes: &[u8]) -> Result<Option<(PokedexHeader, usize)>, PokedexError> {
+    if !bytes.starts_with(&MAGIC) {
+        return Ok(None);
+    }
+    let (header, len) = decode_from_slice::<PokedexHeader, _>(bytes, config::standard())
+        .map_err(PokedexError::Decode)?;
+    Ok(Some((header, len)))
+}
+
+/// Decodes a pokedex file, migrating older layouts to the current `Pokemon`.
+pub fn decode_pokedex(bytes: &[u8]) -> Result<Vec<Pokemon>, PokedexError> {
+    let Some((header, header_len)) = read_header(bytes)? else {
+        // Files written before the header existed are a bare Vec of the version 1
+        // layout, or of the version 0 one from the first release
+        if let Some(records) = decode_headerless::<PokemonV1>(bytes) {
+            return Ok(migrate_v1(records));
+        }
+        let records = decode_headerless::<PokemonV0>(bytes).ok_or(PokedexError::NotAPokedex)?;
+        return Ok(migrate_v1(migrate_v0(records)?));
+    };
+
+    let payload = &bytes[header_len..];
+    let crc = crc32fast::hash(payload);
+    if crc != header.crc {
+        return Err(PokedexError::ChecksumMismatch { expected: header.crc, found: crc });
+    }
+
+    // Older versions get a migration arm here as the schema evolves
+    let pokemon = match header.version {
+        1 => migrate_v1(decode_records::<PokemonV1>(payload)?),
+        2 => migrate_v2(decode_records::<PokemonV2>(payload)?),
+        SCHEMA_VERSION => decode_records::<Pokemon>(payload)?,
+        found => return Err(PokedexError::UnsupportedVersion { found, newest: SCHEMA_VERSION }),
+    };
+    if pokemon.len() != header.record_count as usize {
+        return Err(PokedexError::CountMismatch { expected: header.record_count, found: pokemon.len() });
+    }
+    Ok(pokemon)
+}
+
+fn decode_records<T: Decode<()>>(payload: &[u8]) -> Result<Vec<T>, PokedexError> {
+    let (records, _) = decode_from_slice::<Vec<T>, _>(payload, config::standard())
+        .map_err(PokedexError::Decode)?;
+    Ok(records)
+}
+
+// Only accepts a decode that uses up every byte, since either legacy layout can
+// sometimes read the start of the other one
+fn decode_headerless<T: Decode<()>>(bytes: &[u8]) -> Option<Vec<T>> {
+    match decode_from_slice::<Vec<T>, _>(bytes, config::standard()) {
+        Ok((records, len)) if len == bytes.len() => Some(records),
+        _ => None,
+    }
+}
+
+/// `Pokemon` as the first release stored it, with types as text.
+#[derive(Decode)]
+#[cfg_attr(test, derive(Encode))]
+struct PokemonV0 {
+    generation: u8,
+    name: String,
+    form: Option<String>,
+    type1: String,
+    type2: Option<String>,
+    total: u16,
+    hp: u8,
+    attack: u8,
+    defense: u8,
+    sp_atk: u8,
+    sp_def: u8,
+    speed: u8,
+    height: f32,
+    weight: f32,
+}
+
+fn migrate_v0(records: Vec<PokemonV0>) -> Result<Vec<PokemonV1>, PokedexError> {
+    records.into_iter()
+        .enumerate()
+        .map(|(record, p)| {
+            let parse = |name: &str| {
+                name.parse::<PokemonType>()
+                    .map_err(|_| PokedexError::LegacyType { record, name: name.to_string() })
+            };
+            // The original converter stored a blank Type2 as None, but a file
+            // written by hand may not have
+            let type2 = match p.type2.as_deref().map(str::trim) {
+                None | Some("") => None,
+                Some(name) => Some(parse(name)?),
+            };
+            Ok(PokemonV1 {
+                type1: parse(&p.type1)?,
+                type2,
+                generation: p.generation,
+                name: p.name,
+                form: p.form,
+                total: p.total,
+                hp: p.hp,
+                attack: p.attack,
+                defense: p.defense,
+                sp_atk: p.sp_atk,
+                sp_def: p.sp_def,
+                speed: p.speed,
+                height: p.height,
+                weight: p.weight,
+            })
+        })
+        .collect()
+}
+
+/// `Pokemon` as stored by schema version 1, before IDs and dex numbers.
+#[derive(Decode)]
+#[cfg_attr(test, derive(Encode))]
+struct PokemonV1 {
+    generation: u8,
+    name: String,
+    form: Option<String>,
+    type1: PokemonType,
+    type2: Option<PokemonType>,
+    total: u16,
+    hp: u8,
+    attack: u8,
+    defense: u8,
+    sp_atk: u8,
+    sp_def: u8,
+    speed: u8,
+    height: f32,
+    weight: f32,
+}
+
+// Version 1 files don't know dex numbers, so they are looked up where possible
+fn migrate_v1(records: Vec<PokemonV1>) -> Vec<Pokemon> {
+    let mut pokemon: Vec<Pokemon> = records.into_iter()
+        .map(|p| Pokemon {
+            id: Pokemon::make_id(&p.name, p.form.as_deref()),
+            dex_number: 0,
+            form_kind: FormKind::classify(p.form.as_deref()),
+            generation: p.generation,
+            name: p.name,
+            form: p.form,
+            type1: p.type1,
+            type2: p.type2,
+            total: p.total,
+            hp: p.hp,
+            attack: p.attack,
+            defense: p.defense,
+            sp_atk: p.sp_atk,
+            sp_def: p.sp_def,
+            speed: p.speed,
+            height: p.height,
+            weight: p.weight,
+        })
+        .collect();
+    fill_dex_numbers(&mut pokemon);
+    pokemon::assign_base_forms(&mut pokemon);
+    pokemon
+}
+
+/// `Pokemon` as stored by schema version 2, before form kinds.
+#[derive(Decode)]
+#[cfg_attr(test, derive(Encode))]
+struct PokemonV2 {
+    id: String,
+    dex_number: u16,
+    generation: u8,
+    name: String,
+    form: Option<String>,
+    type1: PokemonType,
+    type2: Option<PokemonType>,
+    total: u16,
+    hp: u8,
+    attack: u8,
+    defense: u8,
+    sp_atk: u8,
+    sp_def: u8,
+    speed: u8,
+    height: f32,
+    weight: f32,
+}
+
+// Form kinds only depend on the form text and the dex numbers, so they can be
+// worked out exactly as the converter would
+fn migrate_v2(records: Vec<PokemonV2>) -> Vec<Pokemon> {
+    let mut pokemon: Vec<Pokemon> = records.into_iter()
+        .map(|p| Pokemon {
+            form_kind: FormKind::classify(p.form.as_deref()),
+            id: p.id,
+            dex_number: p.dex_number,
+            generation: p.generation,
+            name: p.name,
+            form: p.form,
+            type1: p.type1,
+            type2: p.type2,
+            total: p.total,
+            hp: p.hp,
+            attack: p.attack,
+            defense: p.defense,
+            sp_atk: p.sp_atk,
+            sp_def: p.sp_def,
+            speed: p.speed,
+            height: p.height,
+            weight: p.weight,
+        })
+        .collect();
+    pokemon::assign_base_forms(&mut pokemon);
+    pokemon
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{ids, pokedex, pokemon};
+
+    // A file with a header for any version around the given payload
+    fn file(version: u16, record_count: u32, payload: &[u8]) -> Vec<u8> {
+        let header = PokedexHeader {
+            magic: MAGIC,
+            version,
+            record_count,
+            source_hash: [7; 32],
+            crc: crc32fast::hash(payload),
+        };
+        let mut bytes = encode_to_vec(&header, config::standard()).unwrap();
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    // A current entry written out in an older layout
+    fn v0(p: &Pokemon) -> PokemonV0 {
+        PokemonV0 {
+            generation: p.generation,
+            name: p.name.clone(),
+            form: p.form.clone(),
+            type1: p.type1.to_string(),
+            type2: p.type2.map(|t| t.to_string()),
+            total: p.total,
+            hp: p.hp,
+            attack: p.attack,
+            defense: p.defense,
+            sp_atk: p.sp_atk,
+            sp_def: p.sp_def,
+            speed: p.speed,
+            height: p.height,
+            weight: p.weight,
+        }
+    }
+
+    fn v1(p: &Pokemon) -> PokemonV1 {
+        PokemonV1 {
+            generation: p.generation,
+            name: p.name.clone(),
+            form: p.form.clone(),
+            type1: p.type1,
+            type2: p.type2,
+            total: p.total,
+            hp: p.hp,
+            attack: p.attack,
+            defense: p.defense,
+            sp_atk: p.sp_atk,
+            sp_def: p.sp_def,
+            speed: p.speed,
+            height: p.height,
+            weight: p.weight,
+        }
+    }
+
+    fn v2(p: &Pokemon) -> PokemonV2 {
+        PokemonV2 {
+            id: p.id.clone(),
+            dex_number: p.dex_number,
+            generation: p.generation,
+            name: p.name.clone(),
+            form: p.form.clone(),
+            type1: p.type1,
+            type2: p.type2,
+            total: p.total,
+            hp: p.hp,
+            attack: p.attack,
+            defense: p.defense,
+            sp_atk: p.sp_atk,
+            sp_def: p.sp_def,
+            speed: p.speed,
+            height: p.height,
+            weight: p.weight,
+        }
+    }
+
+    #[test]
+    fn encode_then_decode_round_trips() {
+        let bytes = encode_pokedex(&pokedex(), [7; 32]).unwrap();
+        let (header, _) = read_header(&bytes).unwrap().unwrap();
+        assert_eq!(header.version, SCHEMA_VERSION);
+        assert_eq!(header.record_count as usize, pokedex().len());
+        assert_eq!(header.source_hash_hex(), "07".repeat(32));
+        assert_eq!(decode_pokedex(&bytes).unwrap(), pokedex());
+    }
+
+    #[test]
+    fn a_flipped_payload_byte_fails_the_crc() {
+        let mut bytes = encode_pokedex(&pokedex(), [0; 32]).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        assert!(matches!(decode_pokedex(&bytes), Err(PokedexError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn a_wrong_record_count_is_reported() {
+        let payload = encode_to_vec(&pokedex()[..2], config::standard()).unwrap();
+        let error = decode_pokedex(&file(SCHEMA_VERSION, 3, &payload)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "pokedex data is truncated or corrupt: header says 3 records but 2 were decoded"
+        );
+    }
+
+    #[test]
+    fn newer_and_unknown_versions_are_rejected() {
+        let payload = encode_to_vec(&pokedex()[..2], config::standard()).unwrap();
+        let error = decode_pokedex(&file(SCHEMA_VERSION + 1, 2, &payload)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "pokedex schema version {} is newer than this build understands (up to {}), update the app",
+                SCHEMA_VERSION + 1,
+                SCHEMA_VERSION
+            )
+        );
+        let error = decode_pokedex(&file(0, 2, &payload)).unwrap_err();
+        assert!(matches!(error, PokedexError::UnsupportedVersion { found: 0, .. }));
+    }
+
+    #[test]
+    fn junk_is_not_a_pokedex() {
+        assert!(matches!(decode_pokedex(b"definitely not bincode"), Err(PokedexError::NotAPokedex)));
+    }
+
+    #[test]
+    fn version_1_files_get_ids_dex_numbers_and_form_kinds() {
+        let records: Vec<PokemonV1> = pokedex().iter().map(v1).collect();
+        let payload = encode_to_vec(&records, config::standard()).unwrap();
+        assert_eq!(decode_pokedex(&file(1, records.len() as u32, &payload)).unwrap(), pokedex());
+
+        // Not in the built-in dataset, so its number stays unknown
+        let fakemon = Pokemon { name: "Fakemon".to_string(), form: None, ..pokemon("tauros") };
+        let payload = encode_to_vec(vec![v1(&fakemon)], config::standard()).unwrap();
+        let migrated = decode_pokedex(&file(1, 1, &payload)).unwrap();
+        assert_eq!((migrated[0].id.as_str(), migrated[0].dex_number), ("fakemon", 0));
+    }
+
+    #[test]
+    fn headerless_version_1_files_are_migrated() {
+        let records: Vec<PokemonV1> = pokedex().iter().map(v1).collect();
+        let bytes = encode_to_vec(&records, config::standard()).unwrap();
+        assert_eq!(decode_pokedex(&bytes).unwrap(), pokedex());
+    }
+
+    #[test]
+    fn headerless_version_0_files_parse_their_text_types() {
+        let records: Vec<PokemonV0> = pokedex().iter().map(v0).collect();
+        let bytes = encode_to_vec(&records, config::standard()).unwrap();
+        assert_eq!(decode_pokedex(&bytes).unwrap(), pokedex());
+
+        let mut records = vec![v0(&pokemon("charmander")), v0(&pokemon("vulpix"))];
+        records[0].type2 = Some(String::new());
+        records[1].type1 = "Shadow".to_string();
+        let bytes = encode_to_vec(&records, config::standard()).unwrap();
+        assert_eq!(
+            decode_pokedex(&bytes).unwrap_err().to_string(),
+            "old pokedex file can't be migrated: record 1 has unknown type 'Shadow'"
+        );
+    }
+
+    #[test]
+    fn version_2_files_get_form_kinds() {
+        let records: Vec<PokemonV2> = pokedex().iter().map(v2).collect();
+        let payload = encode_to_vec(&records, config::standard()).unwrap();
+        let migrated = decode_pokedex(&file(2, records.len() as u32, &payload)).unwrap();
+        assert_eq!(migrated, pokedex());
+        assert_eq!(migrated.iter().find(|p| p.id == "meowstic-male").unwrap().form_kind, FormKind::Base);
+        assert_eq!(migrated.iter().find(|p| p.id == "meowstic-female").unwrap().form_kind, FormKind::Gender);
+    }
+
+    #[test]
+    fn the_embedded_dataset_is_current() {
+        let (header, _) = read_header(DEFAULT_POKEDEX).unwrap().unwrap();
+        assert_eq!(header.version, SCHEMA_VERSION);
+        assert!(default_pokedex().unwrap().iter().all(|p| p.dex_number != 0));
+    }
+
+    #[test]
+    fn file_names_are_detected_as_csv_case_insensitively() {
+        let loaded = load_bytes("NEW.CSV", crate::test_data::csv().as_bytes()).unwrap();
+        assert_eq!(ids(&loaded.pokemon), ids(&pokedex()));
+        assert!(loaded.warnings().is_empty());
+        assert_eq!(file_label("/some/dir/NEW.CSV"), "NEW.CSV");
+    }
+
+    #[test]
+    fn files_without_a_number_column_get_dex_numbers_looked_up() {
+        // The layout of older drops, before the Number column
+        let csv: String = crate::test_data::csv().lines()
+            .map(|line| format!("{}\n", line.split_once(',').unwrap().1))
+            .collect();
+        assert_eq!(load_bytes("old.csv", csv.as_bytes()).unwrap().pokemon, pokedex());
+    }
+
+    #[test]
+    fn skipped_rows_and_unknown_dex_numbers_are_returned_not_printed() {
+        let csv = format!(
+            "{}{}",
+            crate::test_data::csv(),
+            "0,9,Fakemon, ,Normal, ,300,50,50,50,50,50,50,1,10\n\
+             0,9,Badmon, ,Normal, ,300,50,50,50,50,50,abc,1,10\n"
+        );
+        let loaded = load_bytes("new.csv", csv.as_bytes()).unwrap();
+        assert_eq!(loaded.pokemon.len(), pokedex().len() + 1);
+        assert_eq!(loaded.unknown_dex_numbers, ["Fakemon"]);
+        assert_eq!(loaded.warnings(), [
+            "Skipped line 23: Speed 'abc' is not a valid number",
+            "No dex number for 1 Pokemon: Fakemon",
+        ]);
+    }
+
+    #[test]
+    fn types_and_slider_limits_cover_the_whole_dataset() {
+        let dataset = Dataset { pokemon: pokedex(), ..Dataset::default() };
+        let missing = [PokemonType::Bug, PokemonType::Rock, PokemonType::Dark];
+        let expected: Vec<PokemonType> = PokemonType::ALL.into_iter()
+            .filter(|t| !missing.contains(t))
+            .collect();
+        assert_eq!(dataset.types(), expected);
+        // Garchomp at 1.9 m and Heatran at 430 kg
+        assert_eq!(dataset.max_height(), 2.0);
+        assert_eq!(dataset.max_weight(), 430.0);
+
+        assert!(Dataset::default().types().is_empty());
+        assert_eq!(Dataset::default().max_height(), 0.0);
+    }
+}
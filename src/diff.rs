@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::pokemon::Pokemon;
+
+/// What changed between two versions of the dataset. Entries are matched on
+/// `Pokemon::display_name`, i.e. name and form.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DatasetDiff {
+    // In the order of the new dataset
+    pub added: Vec<Pokemon>,
+    // In the order of the old dataset
+    pub removed: Vec<Pokemon>,
+    pub changed: Vec<FieldChange>,
+}
+
+impl DatasetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// One field of one Pokémon that differs, e.g. "Aegislash (Blade Forme) attack 150 → 140".
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub name: String,
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} → {}", self.name, self.field, self.old, self.new)
+    }
+}
+
+pub fn diff_datasets(old: &[Pokemon], new: &[Pokemon]) -> DatasetDiff {
+    let old_by_name: HashMap<String, &Pokemon> = old.iter().map(|p| (p.display_name(), p)).collect();
+    let new_by_name: HashMap<String, &Pokemon> = new.iter().map(|p| (p.display_name(), p)).collect();
+
+    let mut diff = DatasetDiff::default();
+    for p in new {
+        let name = p.display_name();
+        match old_by_name.get(&name) {
+            Some(before) => diff.changed.extend(field_changes(&name, before, p)),
+            None => diff.added.push(p.clone()),
+        }
+    }
+    diff.removed = old.iter()
+        .filter(|p| !new_by_name.contains_key(&p.display_name()))
+        .cloned()
+        .collect();
+    diff
+}
+
+// Every field but the name and form, which are the key. When the old side has
+// no dex number it couldn't know the form kind for sure either, so neither is
+// reported as a change.
+fn field_changes(name: &str, old: &Pokemon, new: &Pokemon) -> Vec<FieldChange> {
+    let type2 = |p: &Pokemon| p.type2.map_or("none".to_string(), |t| t.to_string());
+    let old_known = old.dex_number != 0;
+    let fields = [
+        ("id", old.id.clone(), new.id.clone()),
+        ("dex_number", old.dex_number.to_string(), new.dex_number.to_string()),
+        ("form_kind", old.form_kind.to_string(), new.form_kind.to_string()),
+        ("generation", old.generation.to_string(), new.generation.to_string()),
+        ("type1", old.type1.to_string(), new.type1.to_string()),
+        ("type2", type2(old), type2(new)),
+        ("total", old.total.to_string(), new.total.to_string()),
+        ("hp", old.hp.to_string(), new.hp.to_string()),
+        ("attack", old.attack.to_string(), new.attack.to_string()),
+        ("defense", old.defense.to_string(), new.defense.to_string()),
+        ("sp_atk", old.sp_atk.to_string(), new.sp_atk.to_string()),
+        ("sp_def", old.sp_def.to_string(), new.sp_def.to_string()),
+        ("speed", old.speed.to_string(), new.speed.to_string()),
+        ("height", old.height.to_string(), new.height.to_string()),
+        ("weight", old.weight.to_string(), new.weight.to_string()),
+    ];
+    fields.into_iter()
+        .filter(|&(field, _, _)| old_known || !matches!(field, "dex_number" | "form_kind"))
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange { name: name.to_string(), field, old, new })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pokemon::FormKind;
+    use crate::test_data::{ids, pokedex, pokemon};
+
+    fn changes(diff: &DatasetDiff) -> Vec<String> {
+        diff.changed.iter().map(FieldChange::to_string).collect()
+    }
+
+    #[test]
+    fn identical_datasets_have_an_empty_diff() {
+        assert!(diff_datasets(&pokedex(), &pokedex()).is_empty());
+        assert!(diff_datasets(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn entries_are_matched_on_name_and_form() {
+        let old: Vec<Pokemon> = pokedex().into_iter().filter(|p| p.id != "rotom-wash").collect();
+        let mut new: Vec<Pokemon> = pokedex().into_iter().filter(|p| p.id != "meowstic-female").collect();
+        new.reverse();
+
+        let diff = diff_datasets(&old, &new);
+        assert_eq!(ids(&diff.added), ["rotom-wash"]);
+        assert_eq!(ids(&diff.removed), ["meowstic-female"]);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn changed_fields_are_listed_in_field_order() {
+        let old = pokemon("raichu-alolan");
+        let new = Pokemon { attack: 90, total: 490, ..old.clone() };
+        let retyped = Pokemon { type2: None, ..old.clone() };
+
+        assert_eq!(changes(&diff_datasets(std::slice::from_ref(&old), &[new])), [
+            "Raichu (Alolan Raichu) total 485 → 490",
+            "Raichu (Alolan Raichu) attack 85 → 90",
+        ]);
+        assert_eq!(
+            changes(&diff_datasets(&[old], &[retyped])),
+            ["Raichu (Alolan Raichu) type2 Psychic → none"]
+        );
+    }
+
+    #[test]
+    fn unknown_old_dex_numbers_are_not_reported_as_changes() {
+        let new = pokemon("meowstic-male");
+        let old = Pokemon { dex_number: 0, form_kind: FormKind::Gender, ..new.clone() };
+        assert!(diff_datasets(&[old], std::slice::from_ref(&new)).is_empty());
+
+        let old = Pokemon { dex_number: 677, form_kind: FormKind::Gender, ..new.clone() };
+        assert_eq!(changes(&diff_datasets(&[old], &[new])), [
+            "Meowstic (Male) dex_number 677 → 678",
+            "Meowstic (Male) form_kind Gender → Base",
+        ]);
+    }
+}
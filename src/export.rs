@@ -0,0 +1,213 @@
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use crate::csv_import::HEADERS;
+use crate::pokemon::Pokemon;
+
+/// Formats the results table can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Markdown];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Markdown => "text/markdown",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Csv(csv::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Csv(e) => write!(f, "failed to write CSV: {}", e),
+            ExportError::Json(e) => write!(f, "failed to write JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// Renders the Pokémon in the given format, keeping their order.
+pub fn export(pokemon: &[Pokemon], format: ExportFormat) -> Result<String, ExportError> {
+    match format {
+        ExportFormat::Csv => to_csv(pokemon),
+        ExportFormat::Json => serde_json::to_string_pretty(pokemon).map_err(ExportError::Json),
+        ExportFormat::Markdown => Ok(to_markdown(pokemon)),
+    }
+}
+
+/// Writes an export to a new file in `dir`, named "pokemon_export.csv" and so on
+/// for the first one and numbered "pokemon_export-2.csv", "-3", ... after that, so
+/// an earlier export is never replaced. Returns the path it went to.
+pub fn save_new_file(dir: &Path, format: ExportFormat, contents: &str) -> io::Result<PathBuf> {
+    let mut n = 1;
+    loop {
+        let file_name = match n {
+            1 => format!("pokemon_export.{}", format.extension()),
+            n => format!("pokemon_export-{}.{}", n, format.extension()),
+        };
+        let path = dir.join(file_name);
+        // `create_new` fails rather than truncating a file that is already there
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(contents.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// CSV with the same header row as CompletePokemon.csv, so it can be fed back to `convert`.
+pub fn to_csv(pokemon: &[Pokemon]) -> Result<String, ExportError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(HEADERS).map_err(ExportError::Csv)?;
+    for p in pokemon {
+        writer.write_record([
+            p.dex_number.to_string(),
+            p.generation.to_string(),
+            p.name.clone(),
+            p.form.clone().unwrap_or_default(),
+            p.type1.to_string(),
+            p.type2.map(|t| t.to_string()).unwrap_or_default(),
+            p.total.to_string(),
+            p.hp.to_string(),
+            p.attack.to_string(),
+            p.defense.to_string(),
+            p.sp_atk.to_string(),
+            p.sp_def.to_string(),
+            p.speed.to_string(),
+            p.height.to_string(),
+            p.weight.to_string(),
+        ]).map_err(ExportError::Csv)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| ExportError::Csv(e.into_error().into()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// A table with the same columns as the app's results table.
+pub fn to_markdown(pokemon: &[Pokemon]) -> String {
+    let mut out = String::from(
+        "| Name | Type | HP | Atk | Def | Sp.Atk | Sp.Def | Speed | Total | Height | Weight | Gen |\n\
+         |---|---|--:|--:|--:|--:|--:|--:|--:|--:|--:|--:|\n",
+    );
+    for p in pokemon {
+        let types = match p.type2 {
+            Some(type2) => format!("{}/{}", p.type1, type2),
+            None => p.type1.to_string(),
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} m | {} kg | {} |\n",
+            // A pipe in a name would end the cell early
+            p.display_name().replace('|', "\\|"),
+            types, p.hp, p.attack, p.defense, p.sp_atk, p.sp_def, p.speed,
+            p.total, p.height, p.weight, p.generation
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_import;
+    use crate::sort::{self, SortColumn, SortKey};
+    use crate::test_data::{self, ids, pokedex, pokemon, temp_dir};
+
+    // Results as the table would show them, fastest first
+    fn results() -> Vec<Pokemon> {
+        let mut pokemon = pokedex();
+        sort::sort_pokemon(&mut pokemon, &[SortKey::new(SortColumn::Speed)]);
+        pokemon
+    }
+
+    #[test]
+    fn csv_reads_back_as_the_same_pokemon_in_the_same_order() {
+        let csv = to_csv(&results()).unwrap();
+        assert!(csv.starts_with(test_data::HEADER));
+
+        let imported = csv_import::parse_csv(csv.as_bytes()).unwrap();
+        assert!(imported.skipped.is_empty());
+        assert_eq!(ids(&imported.pokemon)[..3], ["raichu", "tauros", "raichu-alolan"]);
+        assert_eq!(imported.pokemon, results());
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        let odd = Pokemon { form: Some("Wash, \"Spin\" Rotom".to_string()), ..pokemon("rotom-wash") };
+        let csv = to_csv(std::slice::from_ref(&odd)).unwrap();
+        assert!(csv.contains(r#","Wash, ""Spin"" Rotom","#), "{}", csv);
+        assert_eq!(csv_import::parse_csv(csv.as_bytes()).unwrap().pokemon[0].form, odd.form);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let json = export(&results(), ExportFormat::Json).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<Pokemon>>(&json).unwrap(), results());
+    }
+
+    #[test]
+    fn markdown_has_a_row_per_pokemon_and_escapes_pipes() {
+        let piped = Pokemon { form: Some("Wash|Spin".to_string()), ..pokemon("rotom-wash") };
+        let markdown = to_markdown(&[pokemon("charizard"), piped]);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2], "| Charizard | Fire/Flying | 78 | 84 | 78 | 109 | 85 | 100 | 534 | 1.7 m | 90.5 kg | 1 |");
+        assert!(lines[3].starts_with(r"| Rotom (Wash\|Spin) | Electric/Water |"), "{}", lines[3]);
+    }
+
+    #[test]
+    fn saving_never_replaces_an_earlier_export() {
+        let dir = temp_dir("export");
+        let first = save_new_file(&dir, ExportFormat::Csv, "first").unwrap();
+        let second = save_new_file(&dir, ExportFormat::Csv, "second").unwrap();
+        let markdown = save_new_file(&dir, ExportFormat::Markdown, "table").unwrap();
+
+        assert_eq!(first, dir.join("pokemon_export.csv"));
+        assert_eq!(second, dir.join("pokemon_export-2.csv"));
+        assert_eq!(markdown, dir.join("pokemon_export.md"));
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "first");
+        assert_eq!(std::fs::read_to_string(&second).unwrap(), "second");
+
+        assert!(save_new_file(&dir.join("missing"), ExportFormat::Json, "").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use crate::pokemon::{FormKind, Pokemon, PokemonType};
+use crate::sort::{self, SortKey};
+use crate::type_chart;
+
+/// Every rule the filter panel can express, kept free of any Dioxus state so the
+/// GUI and any other frontend share exactly the same semantics.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FilterCriteria {
+    // Case-insensitive substring match on the name, empty matches everything.
+    // A `*` makes it a whole-name wildcard pattern instead, e.g. "char*".
+    pub name: String,
+    // `None` means any type
+    pub type1: Option<PokemonType>,
+    pub type2: Option<PokemonType>,
+    // Types the Pokémon must have, in either slot
+    pub has_types: Vec<PokemonType>,
+    pub min_gen: u8,
+    pub max_gen: u8,
+    pub min_height: f32,
+    pub max_height: f32,
+    pub min_weight: f32,
+    pub max_weight: f32,
+    pub min_hp: u8,
+    pub max_hp: u8,
+    pub min_attack: u8,
+    pub max_attack: u8,
+    pub min_defense: u8,
+    pub max_defense: u8,
+    pub min_sp_atk: u8,
+    pub max_sp_atk: u8,
+    pub min_sp_def: u8,
+    pub max_sp_def: u8,
+    pub min_speed: u8,
+    pub max_speed: u8,
+    pub min_total: u16,
+    pub max_total: u16,
+    // A Pokémon is filtered out if either of its types is in this list
+    pub excluded_types: Vec<PokemonType>,
+    // Empty allows every kind of form, otherwise the Pokémon's must be one of these.
+    // Missing from presets saved before form kinds existed, hence the defaults.
+    #[serde(default)]
+    pub form_kinds: Vec<FormKind>,
+    #[serde(default)]
+    pub excluded_form_kinds: Vec<FormKind>,
+    // Individually hidden Pokémon, keyed by `Pokemon::id`
+    pub excluded_pokemon: Vec<String>,
+    // Defensive matchups, every listed attacking type must apply
+    pub weak_to: Vec<PokemonType>,
+    pub resists: Vec<PokemonType>,
+    pub immune_to: Vec<PokemonType>,
+    // Applied to the matches in priority order, empty keeps dataset order
+    pub sort: Vec<SortKey>,
+}
+
+/// Highest possible base stat total, six stats of 255.
+pub const MAX_TOTAL: u16 = 6 * u8::MAX as u16;
+
+impl Default for FilterCriteria {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            type1: None,
+            type2: None,
+            has_types: Vec::new(),
+            min_gen: 1,
+            max_gen: Pokemon::MAX_GENERATION,
+            min_height: 0.0,
+            max_height: f32::MAX,
+            min_weight: 0.0,
+            max_weight: f32::MAX,
+            min_hp: 0,
+            max_hp: u8::MAX,
+            min_attack: 0,
+            max_attack: u8::MAX,
+            min_defense: 0,
+            max_defense: u8::MAX,
+            min_sp_atk: 0,
+            max_sp_atk: u8::MAX,
+            min_sp_def: 0,
+            max_sp_def: u8::MAX,
+            min_speed: 0,
+            max_speed: u8::MAX,
+            min_total: 0,
+            max_total: MAX_TOTAL,
+            excluded_types: Vec::new(),
+            form_kinds: Vec::new(),
+            excluded_form_kinds: Vec::new(),
+            excluded_pokemon: Vec::new(),
+            weak_to: Vec::new(),
+            resists: Vec::new(),
+            immune_to: Vec::new(),
+            sort: Vec::new(),
+        }
+    }
+}
+
+impl FilterCriteria {
+    pub fn matches(&self, p: &Pokemon) -> bool {
+        self.name_matches(p)
+            && self.type1_matches(p)
+            && self.type2_matches(p)
+            && self.has_types.iter().all(|&t| p.type1 == t || p.type2 == Some(t))
+            && self.gen_matches(p)
+            && self.size_matches(p)
+            && self.stats_match(p)
+            && !self.is_type_excluded(p)
+            && self.form_kind_matches(p)
+            && !self.excluded_pokemon.contains(&p.id)
+            && self.matchups_match(p)
+    }
+
+    /// Rewrites hidden Pokémon stored by display name, as presets and links from
+    /// before IDs did (e.g. "Charizard (Mega Charizard X)"), to their IDs.
+    /// Entries that match neither are left alone.
+    pub fn resolve_excluded_pokemon(&mut self, pokedex: &[Pokemon]) {
+        for key in &mut self.excluded_pokemon {
+            if pokedex.iter().any(|p| p.id == *key) {
+                continue;
+            }
+            if let Some(p) = pokedex.iter().find(|p| p.display_name() == *key) {
+                *key = p.id.clone();
+            }
+        }
+    }
+
+    /// The matching Pokémon, ordered by the sort keys.
+    pub fn apply(&self, pokedex: &[Pokemon]) -> Vec<Pokemon> {
+        let mut matched: Vec<Pokemon> = pokedex.iter()
+            .filter(|p| self.matches(p))
+            .cloned()
+            .collect();
+        sort::sort_pokemon(&mut matched, &self.sort);
+        matched
+    }
+
+    fn name_matches(&self, p: &Pokemon) -> bool {
+        let name = p.name.to_lowercase();
+        let pattern = self.name.to_lowercase();
+        if pattern.contains('*') {
+            wildcard_match(&pattern, &name)
+        } else {
+            name.contains(&pattern)
+        }
+    }
+
+    fn type1_matches(&self, p: &Pokemon) -> bool {
+        self.type1.is_none_or(|wanted| p.type1 == wanted)
+    }
+
+    fn type2_matches(&self, p: &Pokemon) -> bool {
+        // When a specific Type 2 is selected, only Pokémon with that Type 2 pass
+        self.type2.is_none_or(|wanted| p.type2 == Some(wanted))
+    }
+
+    fn gen_matches(&self, p: &Pokemon) -> bool {
+        p.generation >= self.min_gen && p.generation <= self.max_gen
+    }
+
+    fn size_matches(&self, p: &Pokemon) -> bool {
+        p.height >= self.min_height && p.height <= self.max_height
+            && p.weight >= self.min_weight && p.weight <= self.max_weight
+    }
+
+    fn stats_match(&self, p: &Pokemon) -> bool {
+        (self.min_hp..=self.max_hp).contains(&p.hp)
+            && (self.min_attack..=self.max_attack).contains(&p.attack)
+            && (self.min_defense..=self.max_defense).contains(&p.defense)
+            && (self.min_sp_atk..=self.max_sp_atk).contains(&p.sp_atk)
+            && (self.min_sp_def..=self.max_sp_def).contains(&p.sp_def)
+            && (self.min_speed..=self.max_speed).contains(&p.speed)
+            && (self.min_total..=self.max_total).contains(&p.total)
+    }
+
+    fn is_type_excluded(&self, p: &Pokemon) -> bool {
+        self.excluded_types.contains(&p.type1)
+            || p.type2.is_some_and(|t| self.excluded_types.contains(&t))
+    }
+
+    fn form_kind_matches(&self, p: &Pokemon) -> bool {
+        (self.form_kinds.is_empty() || self.form_kinds.contains(&p.form_kind))
+            && !self.excluded_form_kinds.contains(&p.form_kind)
+    }
+
+    fn matchups_match(&self, p: &Pokemon) -> bool {
+        self.weak_to.iter().all(|&t| type_chart::is_weak_to(t, p))
+            && self.resists.iter().all(|&t| type_chart::resists(t, p))
+            && self.immune_to.iter().all(|&t| type_chart::is_immune_to(t, p))
+    }
+}
+
+// Whole-string match where `*` stands for any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, rest) = parts.split_first().expect("split always yields one part");
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+    let Some((last, middle)) = rest.split_last() else {
+        return remaining.is_empty();
+    };
+    for part in middle {
+        match remaining.find(part) {
+            Some(i) => remaining = &remaining[i + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{self, pokedex};
+    use PokemonType::*;
+
+    fn ids(criteria: &FilterCriteria) -> Vec<String> {
+        criteria.apply(&pokedex()).into_iter().map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn default_matches_everything_in_dataset_order() {
+        assert_eq!(ids(&FilterCriteria::default()), test_data::ids(&pokedex()));
+    }
+
+    #[test]
+    fn name_is_a_case_insensitive_substring() {
+        let criteria = FilterCriteria { name: "CHAR".to_string(), ..Default::default() };
+        assert_eq!(ids(&criteria), ["charmander", "charizard", "charizard-mega-x"]);
+    }
+
+    #[test]
+    fn name_with_star_matches_the_whole_name() {
+        let criteria = |name: &str| FilterCriteria { name: name.to_string(), ..Default::default() };
+        assert_eq!(ids(&criteria("char*")), ["charmander", "charizard", "charizard-mega-x"]);
+        assert_eq!(ids(&criteria("*saur")), ["bulbasaur"]);
+        assert_eq!(ids(&criteria("c*d")), ["charizard", "charizard-mega-x"]);
+        assert_eq!(ids(&criteria("mr. *")), ["mr-mime"]);
+        // Without a star at the end the pattern must reach the end of the name
+        assert!(ids(&criteria("char*z")).is_empty());
+        assert!(wildcard_match("a*b*c", "aXbYc"));
+        assert!(!wildcard_match("a*b*c", "acb"));
+    }
+
+    #[test]
+    fn type2_requires_that_exact_second_type() {
+        let criteria = FilterCriteria { type1: Some(Fire), type2: Some(Flying), ..Default::default() };
+        assert_eq!(ids(&criteria), ["charizard"]);
+    }
+
+    #[test]
+    fn has_types_needs_every_type_in_either_slot() {
+        let criteria = FilterCriteria { has_types: vec![Dragon], ..Default::default() };
+        assert_eq!(ids(&criteria), ["garchomp", "charizard-mega-x"]);
+
+        let criteria = FilterCriteria { has_types: vec![Dragon, Fire], ..Default::default() };
+        assert_eq!(ids(&criteria), ["charizard-mega-x"]);
+    }
+
+    #[test]
+    fn excluded_types_apply_to_either_slot() {
+        let criteria = FilterCriteria {
+            has_types: vec![Fire],
+            excluded_types: vec![Flying, Dragon],
+            ..Default::default()
+        };
+        assert_eq!(ids(&criteria), ["charmander", "vulpix", "heatran"]);
+    }
+
+    #[test]
+    fn ranges_are_inclusive() {
+        let criteria = FilterCriteria { min_speed: 100, max_speed: 104, ..Default::default() };
+        assert_eq!(ids(&criteria), [
+            "charizard", "garchomp", "charizard-mega-x", "meowstic-male", "meowstic-female", "tauros-combat-breed",
+        ]);
+
+        let criteria = FilterCriteria { min_gen: 7, max_gen: 9, ..Default::default() };
+        assert_eq!(ids(&criteria), ["raichu-alolan", "vulpix-alolan", "tauros-combat-breed"]);
+    }
+
+    #[test]
+    fn hidden_pokemon_are_matched_by_id() {
+        let criteria = FilterCriteria {
+            name: "charizard".to_string(),
+            excluded_pokemon: vec!["charizard-mega-x".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(&criteria), ["charizard"]);
+    }
+
+    #[test]
+    fn display_names_from_old_presets_resolve_to_ids() {
+        let mut criteria = FilterCriteria {
+            excluded_pokemon: vec![
+                "Charizard (Mega Charizard X)".to_string(),
+                "Nidoran (Female)".to_string(),
+                "bulbasaur".to_string(),
+                "Missingno".to_string(),
+            ],
+            ..Default::default()
+        };
+        criteria.resolve_excluded_pokemon(&pokedex());
+        assert_eq!(criteria.excluded_pokemon, ["charizard-mega-x", "nidoran-female", "bulbasaur", "Missingno"]);
+    }
+
+    #[test]
+    fn form_kinds_allow_any_listed_kind_and_exclusions_win() {
+        let criteria = FilterCriteria { form_kinds: vec![FormKind::Mega, FormKind::Alolan], ..Default::default() };
+        assert_eq!(ids(&criteria), ["charizard-mega-x", "raichu-alolan", "vulpix-alolan"]);
+
+        // Meowstic has no formless entry, so only the second of its gender forms is one
+        let criteria = FilterCriteria { form_kinds: vec![FormKind::Gender, FormKind::Paldean], ..Default::default() };
+        assert_eq!(ids(&criteria), ["meowstic-female", "tauros-combat-breed"]);
+
+        let criteria = FilterCriteria { excluded_form_kinds: vec![FormKind::Mega], ..Default::default() };
+        assert!(!ids(&criteria).contains(&"charizard-mega-x".to_string()));
+
+        let criteria = FilterCriteria {
+            form_kinds: vec![FormKind::Mega],
+            excluded_form_kinds: vec![FormKind::Mega],
+            ..Default::default()
+        };
+        assert!(ids(&criteria).is_empty());
+    }
+
+    #[test]
+    fn matchups_need_every_listed_type() {
+        // Garchomp is 4x weak to Ice and Bulbasaur 2x, Charizard's Flying and Fire cancel out
+        let criteria = FilterCriteria { weak_to: vec![Ice], ..Default::default() };
+        assert_eq!(ids(&criteria), ["bulbasaur", "garchomp"]);
+
+        let criteria = FilterCriteria { immune_to: vec![Electric], ..Default::default() };
+        assert_eq!(ids(&criteria), ["garchomp"]);
+
+        // Garchomp's Water multiplier is also cancelled out, to 1
+        let criteria = FilterCriteria { weak_to: vec![Ice], resists: vec![Water], ..Default::default() };
+        assert_eq!(ids(&criteria), ["bulbasaur"]);
+
+        // An immunity isn't a resistance
+        let criteria = FilterCriteria { resists: vec![Electric], ..Default::default() };
+        assert!(!ids(&criteria).contains(&"garchomp".to_string()));
+    }
+
+    #[test]
+    fn apply_sorts_the_matches() {
+        let criteria = FilterCriteria {
+            has_types: vec![Fire],
+            sort: vec![SortKey::new(crate::sort::SortColumn::Speed)],
+            ..Default::default()
+        };
+        assert_eq!(ids(&criteria), ["charizard", "charizard-mega-x", "heatran", "charmander", "vulpix"]);
+    }
+}
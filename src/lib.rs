@@ -1,78 +1,182 @@
 pub mod pokemon;
-use pokemon::Pokemon;
+pub mod filter;
+pub mod type_chart;
+pub mod sort;
+pub mod query;
+pub mod dataset;
+pub mod csv_import;
+pub mod validate;
+pub mod diff;
+pub mod export;
+pub mod presets;
+pub mod url_state;
+pub mod server;
+#[cfg(test)]
+mod test_data;
+use pokemon::{FormKind, Pokemon, PokemonType};
+use filter::{FilterCriteria, MAX_TOTAL};
+use dataset::{Dataset, DatasetPath};
+use export::ExportFormat;
+use presets::{PresetError, PresetStore};
+use sort::{SortColumn, SortKey};
 
 use dioxus::prelude::*;
-use std::fs;
-use bincode::{config, decode_from_slice};
+use dioxus::html::{FileEngine, HasFileData};
+use std::sync::Arc;
+
+/// How many Pokémon the comparison view holds at once.
+const MAX_PINNED: usize = 6;
+
+/// Whether results come from the server functions in `server` instead of
+/// filtering a dataset held by the app. Only the desktop and mobile builds, and
+/// the web build without a server, keep the Pokémon themselves.
+const SEARCH_ON_SERVER: bool = cfg!(any(feature = "fullstack", feature = "server"));
+
+/// The default dataset, served next to the web build and fetched on startup.
+#[cfg(all(target_arch = "wasm32", not(feature = "fullstack")))]
+const POKEDEX_ASSET: Asset = asset!("/assets/pokedex_default.bin");
 
 #[allow(non_snake_case)]
 pub fn App() -> Element {
-    let pokedex = use_signal(|| {
-        match fs::read("pokedex_default.bin") {
-            Ok(bytes) => {
-                match decode_from_slice::<Vec<Pokemon>, _>(&bytes, config::standard()) {
-                    Ok((data, _)) => data,
-                    Err(e) => {
-                        eprintln!("Failed to decode: {}", e);
-                        Vec::new()
-                    }
-                }
-            },
-            Err(e) => {
-                eprintln!("Failed to read file: {}", e);
-                // Print more detailed error information
-                eprintln!("Current directory: {:?}", std::env::current_dir().unwrap_or_default());
-                Vec::new()
-            }
+    // A file given on the command line wins over the dataset built into the binary,
+    // and either can be replaced at runtime by opening or dropping a file. The
+    // fullstack build leaves the dataset on the server and this stays empty.
+    let dataset_path = try_use_context::<DatasetPath>();
+    let mut dataset = use_signal(move || {
+        if SEARCH_ON_SERVER {
+            return Dataset::default();
         }
+        Dataset::load_startup(dataset_path.as_ref().map(|DatasetPath(p)| p.as_path()))
     });
+    let pokedex = use_memo(move || dataset.read().pokemon.clone());
+    let mut load_error = use_signal(|| None::<String>);
+
+    // The server's dataset, once the fullstack build has asked about it
+    let server_info = use_signal(|| None::<server::PokedexInfo>);
+
+    // True until the web build has downloaded its dataset, or the fullstack
+    // build has heard back from the server
+    let loading = use_signal(|| cfg!(target_arch = "wasm32") || SEARCH_ON_SERVER);
+    #[cfg(any(feature = "fullstack", feature = "server"))]
+    {
+        let mut loading = loading;
+        let mut server_info = server_info;
+        use_hook(move || {
+            spawn(async move {
+                match server::get_pokedex_info().await {
+                    Ok(info) => server_info.set(Some(info)),
+                    Err(e) => load_error.set(Some(format!("Couldn't reach the Pokédex server: {}", e))),
+                }
+                loading.set(false);
+            });
+        });
+    }
+    #[cfg(all(target_arch = "wasm32", not(feature = "fullstack")))]
+    {
+        let mut loading = loading;
+        use_hook(move || {
+            spawn(async move {
+                match dataset::fetch_pokedex(&POKEDEX_ASSET.to_string()).await {
+                    Ok(pokemon) => dataset.set(Dataset {
+                        source: "Built-in dataset".to_string(),
+                        pokemon,
+                        warnings: Vec::new(),
+                    }),
+                    Err(e) => load_error.set(Some(format!("Couldn't load the Pokédex: {}", e))),
+                }
+                loading.set(false);
+            });
+        });
+    }
+
+    // Where the last export went, or why it failed
+    let mut export_status = use_signal(|| None::<String>);
 
     // Filter states
     let mut name_filter = use_signal(|| "".to_string());
-    let mut selected_type1 = use_signal(|| "".to_string());
-    let mut selected_type2 = use_signal(|| "".to_string());
+    let mut selected_type1 = use_signal(|| None::<PokemonType>);
+    let mut selected_type2 = use_signal(|| None::<PokemonType>);
     let mut min_hp = use_signal(|| 0);
     let mut min_attack = use_signal(|| 0);
     let mut min_defense = use_signal(|| 0);
     let mut min_sp_atk = use_signal(|| 0);
     let mut min_sp_def = use_signal(|| 0);
     let mut min_speed = use_signal(|| 0);
+    let mut max_hp = use_signal(|| u8::MAX);
+    let mut max_attack = use_signal(|| u8::MAX);
+    let mut max_defense = use_signal(|| u8::MAX);
+    let mut max_sp_atk = use_signal(|| u8::MAX);
+    let mut max_sp_def = use_signal(|| u8::MAX);
+    let mut max_speed = use_signal(|| u8::MAX);
+    let mut min_total = use_signal(|| 0);
+    let mut max_total = use_signal(|| MAX_TOTAL);
     let mut selected_gen = use_signal(|| 0); 
     
-    // Height and weight filters
+    // Height and weight filters. The upper ends start unbounded so no dataset is
+    // cut off, and the sliders show that as the end of their track.
     let mut min_height = use_signal(|| 0.0);
-    let mut max_height = use_signal(|| 20.0);
+    let mut max_height = use_signal(|| f32::MAX);
     let mut min_weight = use_signal(|| 0.0);
-    let mut max_weight = use_signal(|| 1000.0);
+    let mut max_weight = use_signal(|| f32::MAX);
     
     // Add these new state variables
     let mut min_gen = use_signal(|| 1);
     let mut max_gen = use_signal(|| 9); // Assuming gen 9 is the latest
     
     // Add this state variable for excluded types
-    let mut excluded_types = use_signal(|| Vec::<String>::new());
+    let mut excluded_types = use_signal(Vec::<PokemonType>::new);
+
+    // Types required in either slot
+    let mut has_types = use_signal(Vec::<PokemonType>::new);
+
+    // Type matchup filters
+    let mut weak_to = use_signal(Vec::<PokemonType>::new);
+    let mut resists = use_signal(Vec::<PokemonType>::new);
+    let mut immune_to = use_signal(Vec::<PokemonType>::new);
+
+    // Kinds of form to allow (empty allows all) and to leave out
+    let mut form_kinds = use_signal(Vec::<FormKind>::new);
+    let mut excluded_form_kinds = use_signal(Vec::<FormKind>::new);
+
+    // Results table ordering, set by clicking column headers
+    let mut sort_keys = use_signal(Vec::<SortKey>::new);
+
+    // Text of the search box and why it last failed to parse
+    let mut query_text = use_signal(String::new);
+    let mut query_error = use_signal(|| None::<String>);
+
+    // Saved presets, the one picked in the dropdown and the name being typed.
+    // If the file on disk couldn't be read, saving stays off for the session so
+    // the presets in it aren't overwritten by the empty store.
+    let presets_loaded = use_hook(|| {
+        PresetStore::default_path()
+            .and_then(|path| PresetStore::load(&path))
+            .map_err(|e| e.to_string())
+    });
+    let presets_load_error = use_signal(|| presets_loaded.as_ref().err().cloned());
+    let mut presets = use_signal(|| presets_loaded.clone().unwrap_or_default());
+    let mut selected_preset = use_signal(String::new);
+    let mut preset_name = use_signal(String::new);
+    let mut preset_status = use_signal(|| presets_load_error().map(|e| format!("Couldn't load presets: {}", e)));
+
+    // ID of the Pokémon whose detail panel is open
+    let mut selected_id = use_signal(|| None::<String>);
+
+    // Pokémon pinned for side-by-side comparison, independent of the filters
+    let mut pinned = use_signal(Vec::<Pokemon>::new);
+
+    // Pokémon hidden from the results one by one, keyed by ID
+    let mut excluded_pokemon = use_signal(Vec::<String>::new);
 
     // Add these new state variables
-    let mut excluded_pokemon = use_signal(|| Vec::<String>::new());
     let mut height_comparison = use_signal(|| "any".to_string()); // "any", "taller", or "shorter"
     let mut weight_comparison = use_signal(|| "any".to_string()); // "any", "heavier", or "lighter"
     let mut reference_pokemon = use_signal(|| None::<Pokemon>);
 
     // Extract all unique types
-    let types = use_memo(move || {
-        let mut types = Vec::new();
-        for pokemon in pokedex.read().iter() {
-            if !types.contains(&pokemon.type1) {
-                types.push(pokemon.type1.clone());
-            }
-            if let Some(type2) = &pokemon.type2 {
-                if !type2.is_empty() && !types.contains(type2) {
-                    types.push(type2.clone());
-                }
-            }
-        }
-        types.sort();
-        types
+    let types = use_memo(move || match server_info() {
+        Some(info) => info.types,
+        None => dataset.read().types(),
     });
     
     // Extract all generations
@@ -87,159 +191,380 @@ pub fn App() -> Element {
         gens
     });
 
-    let max_pokemon_height = use_memo(move || {
-        pokedex.read().iter()
-            .map(|p| p.height)
-            .fold(0.0, f32::max)
-            .ceil()
+    let max_pokemon_height = use_memo(move || match server_info() {
+        Some(info) => info.max_height,
+        None => dataset.read().max_height(),
     });
     
-    let max_pokemon_weight = use_memo(move || {
-        pokedex.read().iter()
-            .map(|p| p.weight)
-            .fold(0.0, f32::max)
-            .ceil()
+    let max_pokemon_weight = use_memo(move || match server_info() {
+        Some(info) => info.max_weight,
+        None => dataset.read().max_weight(),
     });
 
-    // Create a derived state for filtered Pokémon
-    let filtered_pokemon = use_memo(move || {
-        let filtered = pokedex.read().iter()
-            .filter(|p| {
-                // Name filter
-                let name_match = p.name.to_lowercase().contains(&name_filter().to_lowercase());
-                
-                // Type filters
-                // For Type 1 filtering
-                let type1_match = selected_type1().is_empty() || selected_type1() == "All Types" || 
-                    p.type1.to_lowercase() == selected_type1().to_lowercase();
-
-                // For Type 2 filtering - completely rewritten with better logging
-                let type2_match = {
-                    if selected_type2().is_empty() || selected_type2() == "Any/None" {
-                        // When "Any/None" is selected, show all Pokémon regardless of Type 2
-                        true
-                    } else {
-                        // When a specific Type 2 is selected, only show Pokémon with that Type 2
-                        let has_matching_type2 = p.type2.as_ref()
-                            .map(|t| !t.is_empty() && t.to_lowercase() == selected_type2().to_lowercase())
-                            .unwrap_or(false);
-                        
-                        has_matching_type2
+    // Collect the filter signals into a plain criteria value
+    let criteria = use_memo(move || FilterCriteria {
+        name: name_filter(),
+        type1: selected_type1(),
+        type2: selected_type2(),
+        has_types: has_types(),
+        min_gen: min_gen(),
+        max_gen: max_gen(),
+        min_height: min_height(),
+        max_height: max_height(),
+        min_weight: min_weight(),
+        max_weight: max_weight(),
+        min_hp: min_hp(),
+        max_hp: max_hp(),
+        min_attack: min_attack(),
+        max_attack: max_attack(),
+        min_defense: min_defense(),
+        max_defense: max_defense(),
+        min_sp_atk: min_sp_atk(),
+        max_sp_atk: max_sp_atk(),
+        min_sp_def: min_sp_def(),
+        max_sp_def: max_sp_def(),
+        min_speed: min_speed(),
+        max_speed: max_speed(),
+        min_total: min_total(),
+        max_total: max_total(),
+        excluded_types: excluded_types(),
+        form_kinds: form_kinds(),
+        excluded_form_kinds: excluded_form_kinds(),
+        excluded_pokemon: excluded_pokemon(),
+        weak_to: weak_to(),
+        resists: resists(),
+        immune_to: immune_to(),
+        sort: sort_keys(),
+    });
+
+    // Push a criteria value back into the filter signals, the inverse of `criteria`.
+    // With the dataset on the server, hidden Pokémon saved by name stay names and
+    // `search_pokemon` matches them instead.
+    let mut apply_criteria = move |mut c: FilterCriteria| {
+        c.resolve_excluded_pokemon(&pokedex.read());
+        name_filter.set(c.name);
+        selected_type1.set(c.type1);
+        selected_type2.set(c.type2);
+        has_types.set(c.has_types);
+        min_gen.set(c.min_gen);
+        max_gen.set(c.max_gen);
+        min_height.set(c.min_height);
+        max_height.set(c.max_height);
+        min_weight.set(c.min_weight);
+        max_weight.set(c.max_weight);
+        min_hp.set(c.min_hp);
+        max_hp.set(c.max_hp);
+        min_attack.set(c.min_attack);
+        max_attack.set(c.max_attack);
+        min_defense.set(c.min_defense);
+        max_defense.set(c.max_defense);
+        min_sp_atk.set(c.min_sp_atk);
+        max_sp_atk.set(c.max_sp_atk);
+        min_sp_def.set(c.min_sp_def);
+        max_sp_def.set(c.max_sp_def);
+        min_speed.set(c.min_speed);
+        max_speed.set(c.max_speed);
+        min_total.set(c.min_total);
+        max_total.set(c.max_total);
+        excluded_types.set(c.excluded_types);
+        form_kinds.set(c.form_kinds);
+        excluded_form_kinds.set(c.excluded_form_kinds);
+        excluded_pokemon.set(c.excluded_pokemon);
+        weak_to.set(c.weak_to);
+        resists.set(c.resists);
+        immune_to.set(c.immune_to);
+        sort_keys.set(c.sort);
+    };
+
+    // Parse the search box and, if it is valid, replace the filters with it.
+    // Sorting and hidden Pokémon aren't part of the query language so they are kept.
+    let mut run_query = move || {
+        match query::parse_query(&query_text()) {
+            Ok(mut c) => {
+                c.sort = sort_keys();
+                c.excluded_pokemon = excluded_pokemon();
+                apply_criteria(c);
+                query_error.set(None);
+            }
+            Err(e) => query_error.set(Some(e.to_string())),
+        }
+    };
+
+    // On the web the filters are mirrored into the URL so a copied link opens the
+    // same results. The URL is read once the dataset has arrived, so hidden Pokémon
+    // saved by name can be matched to IDs, and only then starts being rewritten.
+    #[cfg(feature = "web")]
+    {
+        let mut url_loaded = use_signal(|| false);
+        use_effect(move || {
+            if loading() || url_loaded() {
+                return;
+            }
+            spawn(async move {
+                let search = document::eval("return window.location.search;").join::<String>().await;
+                if let Ok(search) = search {
+                    if !search.is_empty() {
+                        apply_criteria(url_state::from_query_string(&search));
                     }
-                };
-                
-                // Generation filter
-                let gen_match = p.generation >= min_gen() && p.generation <= max_gen();
-            
-                // Height and weight filters
-                let height_match = p.height >= min_height() && p.height <= max_height();
-                let weight_match = p.weight >= min_weight() && p.weight <= max_weight();
-                
-                // Stat filters
-                let hp_match = p.hp >= min_hp();
-                let attack_match = p.attack >= min_attack();
-                let defense_match = p.defense >= min_defense();
-                let sp_atk_match = p.sp_atk >= min_sp_atk();
-                let sp_def_match = p.sp_def >= min_sp_def();
-                let speed_match = p.speed >= min_speed();
-                
-                // Excluded types filter
-                let not_excluded = {
-                    // Check if either type1 or type2 is in the excluded list
-                    let type1_not_excluded = !excluded_types().contains(&p.type1);
-                    let type2_not_excluded = p.type2.as_ref().map_or(true, |t| 
-                        !excluded_types().contains(t)
-                    );
-                    
-                    // A Pokemon is filtered out if either of its types is excluded
-                    type1_not_excluded && type2_not_excluded
-                };
-                
-                // Apply all filters
-                name_match && type1_match && type2_match && gen_match && 
-                height_match && weight_match &&
-                hp_match && attack_match && defense_match && 
-                sp_atk_match && sp_def_match && speed_match && not_excluded
-            })
-            .cloned()
-            .collect::<Vec<_>>();
-
-        // Add debug diagnostics
-        if filtered.is_empty() {
-            eprintln!("\n=== FILTER DEBUGGING ===");
-            eprintln!("No Pokémon matched the current filters:");
-            eprintln!("Type1: '{}', Type2: '{}'", selected_type1(), selected_type2());
-            eprintln!("Generation range: {} - {}", min_gen(), max_gen());
-            eprintln!("Excluded types: {:?}", excluded_types());
-            
-            // Count how many pass each individual filter
-            let pass_type1 = pokedex.read().iter().filter(|p| {
-                selected_type1().is_empty() || selected_type1() == "All Types" || 
-                p.type1.to_lowercase() == selected_type1().to_lowercase()
-            }).count();
-            
-            let pass_type2 = pokedex.read().iter().filter(|p| {
-                if selected_type2().is_empty() {
-                    true
-                } else {
-                    p.type2.as_ref()
-                        .map(|t| !t.is_empty() && t.to_lowercase() == selected_type2().to_lowercase())
-                        .unwrap_or(false)
                 }
-            }).count();
-            
-            eprintln!("Pokémon passing Type1 filter: {}/{}", pass_type1, pokedex.read().len());
-            eprintln!("Pokémon passing Type2 filter: {}/{}", pass_type2, pokedex.read().len());
-            
-            // Sample a few Pokémon to see why they don't match
-            eprintln!("\nSample Pokémon and why they don't match:");
-            for (i, p) in pokedex.read().iter().take(5).enumerate() {
-                let t1_match = selected_type1().is_empty() || p.type1.to_lowercase() == selected_type1().to_lowercase();
-                let t2_match = if selected_type2().is_empty() {
-                    true
-                } else {
-                    p.type2.as_ref()
-                        .map(|t| !t.is_empty() && t.to_lowercase() == selected_type2().to_lowercase())
-                        .unwrap_or(false)
-                };
-                
-                let gen_range_match = p.generation >= min_gen() && p.generation <= max_gen();
-                let type1_excluded = excluded_types().contains(&p.type1);
-                let type2_excluded = p.type2.as_ref().map_or(false, |t| excluded_types().contains(t));
-                
-                eprintln!("#{}: {} (Type1: {}, Type2: {:?}) - Type1 Match: {}, Type2 Match: {}", 
-                    i+1, p.name, p.type1, p.type2, t1_match, t2_match);
-                eprintln!("  Generation match: {} (pokemon gen: {})", gen_range_match, p.generation);
-                eprintln!("  Type exclusions: type1 excluded: {}, type2 excluded: {}", 
-                    type1_excluded, type2_excluded);
+                url_loaded.set(true);
+            });
+        });
+        use_effect(move || {
+            let c = criteria();
+            if !url_loaded() {
+                return;
+            }
+            let query = url_state::to_query_string(&c);
+            let search = if query.is_empty() { String::new() } else { format!("?{}", query) };
+            document::eval(&format!(
+                "history.replaceState(null, \"\", window.location.pathname + {});",
+                serde_json::to_string(&search).unwrap_or_default()
+            ));
+        });
+    }
+
+    // Write a changed preset store to disk, only keeping it if that worked
+    let mut save_presets = move |store: PresetStore, message: String| {
+        if let Some(e) = presets_load_error() {
+            preset_status.set(Some(format!("Presets are read-only until the preset file is fixed: {}", e)));
+            return;
+        }
+        let result: Result<(), PresetError> = PresetStore::default_path()
+            .and_then(|path| store.save(&path));
+        match result {
+            Ok(()) => {
+                presets.set(store);
+                preset_status.set(Some(message));
             }
-            eprintln!("=== END DEBUGGING ===\n");
+            Err(e) => preset_status.set(Some(format!("Couldn't save presets: {}", e))),
         }
+    };
 
-        // For better debugging, print information about the active filters
-        eprintln!("=== FILTER STATUS ===");
-        eprintln!("Type1: '{}', Type2: '{}'", selected_type1(), selected_type2());
-        eprintln!("Pokémon count: {}", filtered.len());
-
-        // If type filters are active, print some examples
-        if !selected_type1().is_empty() || !selected_type2().is_empty() {
-            eprintln!("\nSample Type Data:");
-            for (i, p) in pokedex.read().iter().take(10).enumerate() {
-                eprintln!("#{}: {} - Type1: '{}', Type2: '{:?}'", 
-                         i+1, p.name, p.type1, p.type2);
+    // Load the first of the chosen or dropped files in place of the current dataset
+    let open_files = move |files: Option<Arc<dyn FileEngine>>| async move {
+        if SEARCH_ON_SERVER {
+            return;
+        }
+        let Some(engine) = files else { return };
+        let Some(name) = engine.files().into_iter().next() else { return };
+        let label = dataset::file_label(&name);
+        match engine.read_file(&name).await {
+            Some(bytes) => match dataset::load_bytes(&name, &bytes) {
+                Ok(loaded) => {
+                    dataset.set(Dataset { source: label, warnings: loaded.warnings(), pokemon: loaded.pokemon });
+                    load_error.set(None);
+                    // These point at entries of the old dataset
+                    selected_id.set(None);
+                    pinned.set(Vec::new());
+                }
+                Err(e) => load_error.set(Some(format!("Couldn't open {}: {}", label, e))),
+            },
+            None => load_error.set(Some(format!("Couldn't read {}", label))),
+        }
+    };
+
+    // An unbounded upper end is shown as the dataset's largest value
+    let shown_max_height = max_height().min(max_pokemon_height());
+    let shown_max_weight = max_weight().min(max_pokemon_weight());
+
+    // The fullstack build asks the server again whenever the filters change. The
+    // previous results stay up until the new ones arrive.
+    let search = use_resource(move || {
+        let c = if SEARCH_ON_SERVER { Some(criteria()) } else { None };
+        async move {
+            match c {
+                Some(c) => Some(server::search_pokemon(c).await.map_err(|e| e.to_string())),
+                None => None,
             }
         }
-        eprintln!("===================");
+    });
+    let search_error = use_memo(move || match &*search.read() {
+        Some(Some(Err(e))) => Some(format!("Search failed: {}", e)),
+        _ => None,
+    });
 
-        filtered
+    // Create a derived state for filtered Pokémon
+    let filtered_pokemon = use_memo(move || {
+        if !SEARCH_ON_SERVER {
+            return criteria.read().apply(&pokedex.read());
+        }
+        match &*search.read() {
+            Some(Some(Ok(found))) => found.clone(),
+            _ => Vec::new(),
+        }
     });
 
+    // Loaded by ID so the fullstack build only fetches the one being shown
+    let selected_pokemon = use_resource(move || {
+        let id = selected_id();
+        async move {
+            let id = id?;
+            if SEARCH_ON_SERVER {
+                server::get_pokemon(id).await.map_err(|e| format!("Couldn't load details: {}", e)).transpose()
+            } else {
+                pokedex.read().iter().find(|p| p.id == id).cloned().map(Ok)
+            }
+        }
+    });
+
+    // Skipped rows and the like, from loading the current dataset
+    let dataset_warnings = match server_info() {
+        Some(info) => info.warnings,
+        None => dataset.read().warnings.clone(),
+    };
+
     rsx! {
         div { class: "container",
+            // Dropping a .bin or .csv anywhere on the window opens it
+            ondragover: move |e| e.prevent_default(),
+            ondrop: move |e| {
+                e.prevent_default();
+                open_files(e.files())
+            },
+
             h1 { class: "title", "Pokémon Filter App" }
+
+            div { class: "dataset-bar",
+                if loading() {
+                    span { class: "dataset-source", "Loading Pokédex…" }
+                } else if let Some(info) = server_info() {
+                    span { class: "dataset-source", "Dataset: {info.source} on the server ({info.count} Pokémon)" }
+                } else {
+                    span { class: "dataset-source", "Dataset: {dataset.read().source} ({pokedex.read().len()} Pokémon)" }
+                }
+                // The server's dataset can't be swapped from the browser
+                if !SEARCH_ON_SERVER {
+                    label { class: "reset-button open-button",
+                        "Open Dataset…"
+                        input {
+                            r#type: "file",
+                            accept: ".bin,.csv",
+                            class: "hidden-file-input",
+                            onchange: move |e| open_files(e.files()),
+                        }
+                    }
+                }
+                if let Some(error) = load_error() {
+                    span { class: "query-error", "{error}" }
+                }
+                if !dataset_warnings.is_empty() {
+                    span { class: "query-error", {dataset_warnings.join("; ")} }
+                }
+            }
             
             div { class: "filters",
                 h2 { "Filters" }
+
+                div { class: "filter-row",
+                    label { "Preset: " }
+                    div { class: "search-container",
+                        div { class: "search-box",
+                            select {
+                                value: "{selected_preset}",
+                                onchange: move |e| {
+                                    let name = e.value();
+                                    let preset = presets.read().get(&name).cloned();
+                                    if let Some(preset) = preset {
+                                        apply_criteria(preset.criteria);
+                                        preset_name.set(name.clone());
+                                        preset_status.set(None);
+                                    }
+                                    selected_preset.set(name);
+                                },
+                                option { value: "", "Choose a preset..." }
+                                for preset in presets.read().presets.iter() {
+                                    option { value: "{preset.name}", "{preset.name}" }
+                                }
+                            }
+                            input {
+                                r#type: "text",
+                                value: "{preset_name}",
+                                oninput: move |e| preset_name.set(e.value()),
+                                placeholder: "Preset name"
+                            }
+                        }
+                        div { class: "search-box",
+                            button {
+                                class: "pin-button",
+                                onclick: move |_| {
+                                    let mut store = presets();
+                                    match store.upsert(&preset_name(), criteria()) {
+                                        Ok(()) => {
+                                            let name = preset_name().trim().to_string();
+                                            save_presets(store, format!("Saved '{}'", name));
+                                            selected_preset.set(name);
+                                        }
+                                        Err(e) => preset_status.set(Some(e.to_string())),
+                                    }
+                                },
+                                "Save"
+                            }
+                            button {
+                                class: "pin-button",
+                                disabled: selected_preset().is_empty(),
+                                onclick: move |_| {
+                                    let mut store = presets();
+                                    match store.rename(&selected_preset(), &preset_name()) {
+                                        Ok(()) => {
+                                            let name = preset_name().trim().to_string();
+                                            save_presets(store, format!("Renamed to '{}'", name));
+                                            selected_preset.set(name);
+                                        }
+                                        Err(e) => preset_status.set(Some(e.to_string())),
+                                    }
+                                },
+                                "Rename"
+                            }
+                            button {
+                                class: "pin-button",
+                                disabled: selected_preset().is_empty(),
+                                onclick: move |_| {
+                                    let mut store = presets();
+                                    match store.delete(&selected_preset()) {
+                                        Ok(()) => {
+                                            save_presets(store, format!("Deleted '{}'", selected_preset()));
+                                            selected_preset.set(String::new());
+                                            preset_name.set(String::new());
+                                        }
+                                        Err(e) => preset_status.set(Some(e.to_string())),
+                                    }
+                                },
+                                "Delete"
+                            }
+                        }
+                        if let Some(status) = preset_status() {
+                            span { class: "dataset-source", "{status}" }
+                        }
+                    }
+                }
+
+                div { class: "filter-row",
+                    label { "Search: " }
+                    div { class: "search-container",
+                        div { class: "search-box",
+                            input {
+                                r#type: "text",
+                                value: "{query_text}",
+                                // Re-parse as you type so mistakes show up straight away
+                                oninput: move |e| {
+                                    query_text.set(e.value());
+                                    query_error.set(query::parse_query(&e.value()).err().map(|e| e.to_string()));
+                                },
+                                onkeydown: move |e| {
+                                    if e.key() == Key::Enter {
+                                        run_query();
+                                    }
+                                },
+                                placeholder: "type:fire gen:1-3 spe>=100 bst<500 -type:flying name:char*"
+                            }
+                            button { class: "reset-button search-button", onclick: move |_| run_query(), "Apply" }
+                        }
+                        if let Some(error) = query_error() {
+                            span { class: "query-error", "{error}" }
+                        }
+                    }
+                }
                 
                 div { class: "filter-row",
                     label { "Name: " }
@@ -294,8 +619,9 @@ pub fn App() -> Element {
                 div { class: "filter-row",
                     label { "Type 1: " }
                     select {
-                        value: "{selected_type1}",
-                        onchange: move |e| selected_type1.set(e.value().clone()),
+                        value: selected_type1().map_or("All Types".to_string(), |t| t.to_string()),
+                        // "All Types" doesn't parse, which clears the filter
+                        onchange: move |e| selected_type1.set(e.value().parse().ok()),
                         option { value: "All Types", "All Types" }  // Make value match text
                         {
                             types.read().iter()
@@ -312,8 +638,8 @@ pub fn App() -> Element {
                 div { class: "filter-row",
                     label { "Type 2: " }
                     select {
-                        value: "{selected_type2}",
-                        onchange: move |e| selected_type2.set(e.value().clone()),
+                        value: selected_type2().map_or("Any/None".to_string(), |t| t.to_string()),
+                        onchange: move |e| selected_type2.set(e.value().parse().ok()),
                         option { value: "Any/None", "Any/None" }  // Make value match text
                         {
                             types.read().iter()
@@ -333,18 +659,17 @@ pub fn App() -> Element {
                     div { class: "excluded-types-container",
                         // Show currently excluded types as tags with matching type colors
                         div { class: "excluded-types-tags",
-                            {excluded_types().iter().map(|type_name| {
-                                let type_name_owned = type_name.clone();
+                            {excluded_types().into_iter().map(|type_name| {
                                 rsx! {
                                     div { 
                                         // Apply both classes to get styling and type-specific color
-                                        class: "excluded-type-tag {type_name.to_lowercase()}", 
+                                        class: "excluded-type-tag {type_name.css_class()}", 
                                         "{type_name}"
                                         button { 
                                             class: "remove-tag",
                                             onclick: move |_| {
                                                 let mut current = excluded_types();
-                                                current.retain(|t| t != &type_name_owned);
+                                                current.retain(|t| *t != type_name);
                                                 excluded_types.set(current);
                                             },
                                             "×"
@@ -358,11 +683,12 @@ pub fn App() -> Element {
                         select {
                             value: "",
                             onchange: move |e| {
-                                let value = e.value().clone();
-                                if !value.is_empty() && !excluded_types().contains(&value) {
-                                    let mut current = excluded_types();
-                                    current.push(value);
-                                    excluded_types.set(current);
+                                if let Ok(value) = e.value().parse::<PokemonType>() {
+                                    if !excluded_types().contains(&value) {
+                                        let mut current = excluded_types();
+                                        current.push(value);
+                                        excluded_types.set(current);
+                                    }
                                 }
                             },
                             option { value: "", "Select type to exclude..." }
@@ -380,8 +706,45 @@ pub fn App() -> Element {
                     }
                 }
                 
+                TypeTagFilter { label: "Has Types: ", placeholder: "Select required type...", selected: has_types }
+                TypeTagFilter { label: "Weak To: ", placeholder: "Select attacking type...", selected: weak_to }
+                TypeTagFilter { label: "Resists: ", placeholder: "Select attacking type...", selected: resists }
+                TypeTagFilter { label: "Immune To: ", placeholder: "Select attacking type...", selected: immune_to }
+                FormKindTagFilter { label: "Only Forms: ", placeholder: "Any kind of form", selected: form_kinds }
+                FormKindTagFilter { label: "Exclude Forms: ", placeholder: "Select form kind to exclude...", selected: excluded_form_kinds }
+
+                if !excluded_pokemon().is_empty() {
+                    div { class: "filter-row",
+                        label { "Hidden Pokémon: " }
+                        div { class: "excluded-types-tags",
+                            {excluded_pokemon().into_iter().map(|key| {
+                                // Show the name, or the bare ID if this dataset doesn't have it
+                                let label = pokedex.read().iter()
+                                    .find(|p| p.id == key)
+                                    .map_or_else(|| key.clone(), |p| p.display_name());
+                                let key_owned = key.clone();
+                                rsx! {
+                                    div { key: "{key}",
+                                        class: "excluded-type-tag hidden-pokemon-tag",
+                                        "{label}"
+                                        button {
+                                            class: "remove-tag",
+                                            onclick: move |_| {
+                                                let mut current = excluded_pokemon();
+                                                current.retain(|k| k != &key_owned);
+                                                excluded_pokemon.set(current);
+                                            },
+                                            "×"
+                                        }
+                                    }
+                                }
+                            })}
+                        }
+                    }
+                }
+                
                 div { class: "filter-row",
-                    label { "Height Range: {min_height:.1} - {max_height:.1} m" }
+                    label { "Height Range: {min_height:.1} - {shown_max_height:.1} m" }
                     div { class: "range-inputs",
                         input {
                             r#type: "range",
@@ -402,10 +765,12 @@ pub fn App() -> Element {
                             min: "0", 
                             max: "{max_pokemon_height()}",
                             step: "0.1",
-                            value: "{max_height}",
+                            value: "{shown_max_height}",
                             oninput: move |e| {
                                 if let Ok(val) = e.value().parse::<f32>() {
-                                    if val >= min_height() {
+                                    if val >= max_pokemon_height() {
+                                        max_height.set(f32::MAX);
+                                    } else if val >= min_height() {
                                         max_height.set(val);
                                     }
                                 }
@@ -415,7 +780,7 @@ pub fn App() -> Element {
                 }
                 
                 div { class: "filter-row",
-                    label { "Weight Range: {min_weight:.1} - {max_weight:.1} kg" }
+                    label { "Weight Range: {min_weight:.1} - {shown_max_weight:.1} kg" }
                     div { class: "range-inputs",
                         input {
                             r#type: "range",
@@ -436,10 +801,12 @@ pub fn App() -> Element {
                             min: "0", 
                             max: "{max_pokemon_weight()}",
                             step: "0.1",
-                            value: "{max_weight}",
+                            value: "{shown_max_weight}",
                             oninput: move |e| {
                                 if let Ok(val) = e.value().parse::<f32>() {
-                                    if val >= min_weight() {
+                                    if val >= max_pokemon_weight() {
+                                        max_weight.set(f32::MAX);
+                                    } else if val >= min_weight() {
                                         max_weight.set(val);
                                     }
                                 }
@@ -448,91 +815,40 @@ pub fn App() -> Element {
                     }
                 }
                 
+                StatRangeFilter { label: "HP", min: min_hp, max: max_hp }
+                StatRangeFilter { label: "Attack", min: min_attack, max: max_attack }
+                StatRangeFilter { label: "Defense", min: min_defense, max: max_defense }
+                StatRangeFilter { label: "Sp. Atk", min: min_sp_atk, max: max_sp_atk }
+                StatRangeFilter { label: "Sp. Def", min: min_sp_def, max: max_sp_def }
+                StatRangeFilter { label: "Speed", min: min_speed, max: max_speed }
+
                 div { class: "filter-row",
-                    label { "Min HP: {min_hp}" }
-                    input {
-                        r#type: "range",
-                        min: "0", 
-                        max: "255",
-                        value: "{min_hp}",
-                        oninput: move |e| {
-                            if let Ok(val) = e.value().parse::<u8>() {
-                                min_hp.set(val);
-                            }
-                        }
-                    }
-                }
-                
-                div { class: "filter-row",
-                    label { "Min Attack: {min_attack}" }
-                    input {
-                        r#type: "range",
-                        min: "0", 
-                        max: "255",
-                        value: "{min_attack}",
-                        oninput: move |e| {
-                            if let Ok(val) = e.value().parse::<u8>() {
-                                min_attack.set(val);
-                            }
-                        }
-                    }
-                }
-                
-                div { class: "filter-row",
-                    label { "Min Defense: {min_defense}" }
-                    input {
-                        r#type: "range",
-                        min: "0", 
-                        max: "255",
-                        value: "{min_defense}",
-                        oninput: move |e| {
-                            if let Ok(val) = e.value().parse::<u8>() {
-                                min_defense.set(val);
-                            }
-                        }
-                    }
-                }
-                
-                div { class: "filter-row",
-                    label { "Min Sp. Atk: {min_sp_atk}" }
-                    input {
-                        r#type: "range",
-                        min: "0", 
-                        max: "255",
-                        value: "{min_sp_atk}",
-                        oninput: move |e| {
-                            if let Ok(val) = e.value().parse::<u8>() {
-                                min_sp_atk.set(val);
-                            }
-                        }
-                    }
-                }
-                
-                div { class: "filter-row",
-                    label { "Min Sp. Def: {min_sp_def}" }
-                    input {
-                        r#type: "range",
-                        min: "0", 
-                        max: "255",
-                        value: "{min_sp_def}",
-                        oninput: move |e| {
-                            if let Ok(val) = e.value().parse::<u8>() {
-                                min_sp_def.set(val);
+                    label { "Total (BST): {min_total} - {max_total}" }
+                    div { class: "range-inputs",
+                        input {
+                            r#type: "range",
+                            min: "0",
+                            max: "{MAX_TOTAL}",
+                            value: "{min_total}",
+                            oninput: move |e| {
+                                if let Ok(val) = e.value().parse::<u16>() {
+                                    if val <= max_total() {
+                                        min_total.set(val);
+                                    }
+                                }
                             }
                         }
-                    }
-                }
-                
-                div { class: "filter-row",
-                    label { "Min Speed: {min_speed}" }
-                    input {
-                        r#type: "range",
-                        min: "0", 
-                        max: "255",
-                        value: "{min_speed}",
-                        oninput: move |e| {
-                            if let Ok(val) = e.value().parse::<u8>() {
-                                min_speed.set(val);
+                        input {
+                            r#type: "range",
+                            min: "0",
+                            max: "{MAX_TOTAL}",
+                            value: "{max_total}",
+                            oninput: move |e| {
+                                if let Ok(val) = e.value().parse::<u16>() {
+                                    if val >= min_total() {
+                                        max_total.set(val);
+                                    }
+                                }
                             }
                         }
                     }
@@ -542,8 +858,8 @@ pub fn App() -> Element {
                     class: "reset-button",
                     onclick: move |_| {
                         name_filter.set("".to_string());
-                        selected_type1.set("All Types".to_string());  // Change to match dropdown
-                        selected_type2.set("Any/None".to_string());   // Change to match dropdown
+                        selected_type1.set(None);
+                        selected_type2.set(None);
                         selected_gen.set(0);
                         min_hp.set(0);
                         min_attack.set(0);
@@ -551,13 +867,31 @@ pub fn App() -> Element {
                         min_sp_atk.set(0);
                         min_sp_def.set(0);
                         min_speed.set(0);
+                        max_hp.set(u8::MAX);
+                        max_attack.set(u8::MAX);
+                        max_defense.set(u8::MAX);
+                        max_sp_atk.set(u8::MAX);
+                        max_sp_def.set(u8::MAX);
+                        max_speed.set(u8::MAX);
+                        min_total.set(0);
+                        max_total.set(MAX_TOTAL);
+                        has_types.set(Vec::new());
+                        query_text.set(String::new());
+                        query_error.set(None);
                         min_height.set(0.0);
-                        max_height.set(max_pokemon_height());
+                        max_height.set(f32::MAX);
                         min_weight.set(0.0);
-                        max_weight.set(max_pokemon_weight());
+                        max_weight.set(f32::MAX);
                         min_gen.set(1);
                         max_gen.set(9); // Or whatever your max generation is
                         excluded_types.set(Vec::new());
+                        weak_to.set(Vec::new());
+                        resists.set(Vec::new());
+                        immune_to.set(Vec::new());
+                        form_kinds.set(Vec::new());
+                        excluded_form_kinds.set(Vec::new());
+                        sort_keys.set(Vec::new());
+                        excluded_pokemon.set(Vec::new());
                     },
                     "Reset Filters"
                 }
@@ -565,34 +899,301 @@ pub fn App() -> Element {
 
             div { class: "results",
                 h2 { "Results" }
+
+                div { class: "export-bar",
+                    span { "Export: " }
+                    for format in ExportFormat::ALL {
+                        button {
+                            class: "pin-button",
+                            onclick: move |_| {
+                                // Already filtered and in table order
+                                let status = export::export(&filtered_pokemon.read(), format)
+                                    .map_err(|e| e.to_string())
+                                    .and_then(|contents| save_export(format, &contents));
+                                export_status.set(Some(status.unwrap_or_else(|e| e)));
+                            },
+                            "{format}"
+                        }
+                    }
+                    if let Some(status) = export_status() {
+                        span { class: "dataset-source", "{status}" }
+                    }
+                }
+
+                match selected_pokemon.cloned() {
+                    Some(Some(Ok(pokemon))) => rsx! {
+                        PokemonDetail { pokemon, on_close: move |_| selected_id.set(None) }
+                    },
+                    Some(Some(Err(error))) => rsx! {
+                        span { class: "query-error", "{error}" }
+                    },
+                    _ => rsx! {},
+                }
+
+                if !pinned().is_empty() {
+                    ComparisonView { pinned }
+                }
                 
                 table { class: "pokemon-table",
                     thead {
                         tr {
-                            th { "Name" }
+                            SortHeader { label: "Name", column: SortColumn::Name, sort_keys }
                             th { "Type" }
-                            th { "HP" }
-                            th { "Atk" }
-                            th { "Def" }
-                            th { "Sp.Atk" }
-                            th { "Sp.Def" }
-                            th { "Speed" }
-                            th { "Total" }
-                            th { "Height" }
-                            th { "Weight" }
-                            th { "Gen" }
+                            SortHeader { label: "HP", column: SortColumn::Hp, sort_keys }
+                            SortHeader { label: "Atk", column: SortColumn::Attack, sort_keys }
+                            SortHeader { label: "Def", column: SortColumn::Defense, sort_keys }
+                            SortHeader { label: "Sp.Atk", column: SortColumn::SpAtk, sort_keys }
+                            SortHeader { label: "Sp.Def", column: SortColumn::SpDef, sort_keys }
+                            SortHeader { label: "Speed", column: SortColumn::Speed, sort_keys }
+                            SortHeader { label: "Total", column: SortColumn::Total, sort_keys }
+                            SortHeader { label: "Height", column: SortColumn::Height, sort_keys }
+                            SortHeader { label: "Weight", column: SortColumn::Weight, sort_keys }
+                            SortHeader { label: "Gen", column: SortColumn::Generation, sort_keys }
+                            th { "Actions" }
                         }
                     }
                     tbody {
                         tr {
-                            td { colspan: "12", class: "result-count",
-                                "Found {filtered_pokemon().len()} Pokémon"
+                            td { colspan: "13", class: "result-count",
+                                if loading() {
+                                    "Loading Pokédex…"
+                                } else if let Some(error) = search_error() {
+                                    "{error}"
+                                } else {
+                                    "Found {filtered_pokemon().len()} Pokémon"
+                                }
                             }
                         }
                         
                         for pokemon in filtered_pokemon().iter() {
-                            PokemonRow { pokemon: pokemon.clone() }
+                            PokemonRow {
+                                pokemon: pokemon.clone(),
+                                is_pinned: pinned().iter().any(|c| c.id == pokemon.id),
+                                can_pin: pinned().len() < MAX_PINNED,
+                                on_select: move |p: Pokemon| selected_id.set(Some(p.id)),
+                                on_toggle_pin: move |p: Pokemon| {
+                                    let mut current = pinned();
+                                    if let Some(i) = current.iter().position(|c| c.id == p.id) {
+                                        current.remove(i);
+                                    } else if current.len() < MAX_PINNED {
+                                        current.push(p);
+                                    }
+                                    pinned.set(current);
+                                },
+                                on_hide: move |p: Pokemon| {
+                                    let mut current = excluded_pokemon();
+                                    if !current.contains(&p.id) {
+                                        current.push(p.id);
+                                        excluded_pokemon.set(current);
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Hands exported text to the user as a new file in their Downloads folder,
+// returning a message saying where it went
+#[cfg(not(target_arch = "wasm32"))]
+fn save_export(format: ExportFormat, contents: &str) -> Result<String, String> {
+    let dir = dirs::download_dir()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let path = export::save_new_file(&dir, format, contents)
+        .map_err(|e| format!("Couldn't write to {}: {}", dir.display(), e))?;
+    Ok(format!("Saved {}", path.display()))
+}
+
+// In the browser there is no filesystem, so trigger a download instead
+#[cfg(target_arch = "wasm32")]
+fn save_export(format: ExportFormat, contents: &str) -> Result<String, String> {
+    let file_name = format!("pokemon_export.{}", format.extension());
+    let script = format!(
+        r#"const blob = new Blob([{}], {{ type: {:?} }});
+        const link = document.createElement("a");
+        link.href = URL.createObjectURL(blob);
+        link.download = {:?};
+        link.click();
+        URL.revokeObjectURL(link.href);"#,
+        serde_json::to_string(contents).map_err(|e| e.to_string())?,
+        format.mime_type(),
+        file_name
+    );
+    document::eval(&script);
+    Ok(format!("Downloaded {}", file_name))
+}
+
+// Min-max pair of sliders for one base stat, in the same style as height/weight
+#[component]
+fn StatRangeFilter(label: String, min: Signal<u8>, max: Signal<u8>) -> Element {
+    rsx! {
+        div { class: "filter-row",
+            label { "{label}: {min} - {max}" }
+            div { class: "range-inputs",
+                input {
+                    r#type: "range",
+                    min: "0",
+                    max: "255",
+                    value: "{min}",
+                    oninput: move |e| {
+                        if let Ok(val) = e.value().parse::<u8>() {
+                            if val <= max() {
+                                min.set(val);
+                            }
+                        }
+                    }
+                }
+                input {
+                    r#type: "range",
+                    min: "0",
+                    max: "255",
+                    value: "{max}",
+                    oninput: move |e| {
+                        if let Ok(val) = e.value().parse::<u8>() {
+                            if val >= min() {
+                                max.set(val);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Clickable column header; shift-click adds the column as a secondary sort key
+#[component]
+fn SortHeader(label: String, column: SortColumn, sort_keys: Signal<Vec<SortKey>>) -> Element {
+    let position = sort_keys().iter().position(|k| k.column == column);
+    let indicator = match position {
+        Some(i) => {
+            let arrow = sort_keys()[i].direction.arrow();
+            // Only number the keys once there is more than one
+            if sort_keys().len() > 1 { format!(" {}{}", arrow, i + 1) } else { format!(" {}", arrow) }
+        }
+        None => String::new(),
+    };
+
+    rsx! {
+        th {
+            class: "sortable",
+            onclick: move |e| {
+                let mut current = sort_keys();
+                sort::toggle_sort(&mut current, column, e.modifiers().shift());
+                sort_keys.set(current);
+            },
+            "{label}{indicator}"
+        }
+    }
+}
+
+// A filter row holding a removable tag per selected type, styled like Exclude Types
+#[component]
+fn TypeTagFilter(label: String, placeholder: String, selected: Signal<Vec<PokemonType>>) -> Element {
+    rsx! {
+        div { class: "filter-row",
+            label { "{label}" }
+            div { class: "excluded-types-container",
+                div { class: "excluded-types-tags",
+                    {selected().into_iter().map(|type_name| {
+                        rsx! {
+                            div {
+                                class: "excluded-type-tag {type_name.css_class()}",
+                                "{type_name}"
+                                button {
+                                    class: "remove-tag",
+                                    onclick: move |_| {
+                                        let mut current = selected();
+                                        current.retain(|t| *t != type_name);
+                                        selected.set(current);
+                                    },
+                                    "×"
+                                }
+                            }
+                        }
+                    })}
+                }
+
+                select {
+                    value: "",
+                    onchange: move |e| {
+                        if let Ok(value) = e.value().parse::<PokemonType>() {
+                            if !selected().contains(&value) {
+                                let mut current = selected();
+                                current.push(value);
+                                selected.set(current);
+                            }
+                        }
+                    },
+                    option { value: "", "{placeholder}" }
+                    {
+                        PokemonType::ALL.iter()
+                            .filter(|type_name| !selected().contains(type_name))
+                            .map(|type_name| {
+                                rsx! {
+                                    option { value: "{type_name}", "{type_name}" }
+                                }
+                            })
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Like `TypeTagFilter` for form kinds. The dropdown also offers "Regional",
+/// which adds all four regional kinds at once.
+#[component]
+fn FormKindTagFilter(label: String, placeholder: String, selected: Signal<Vec<FormKind>>) -> Element {
+    rsx! {
+        div { class: "filter-row",
+            label { "{label}" }
+            div { class: "excluded-types-container",
+                div { class: "excluded-types-tags",
+                    {selected().into_iter().map(|kind| {
+                        rsx! {
+                            div {
+                                class: "excluded-type-tag form-kind-tag",
+                                "{kind}"
+                                button {
+                                    class: "remove-tag",
+                                    onclick: move |_| {
+                                        let mut current = selected();
+                                        current.retain(|k| *k != kind);
+                                        selected.set(current);
+                                    },
+                                    "×"
+                                }
+                            }
+                        }
+                    })}
+                }
+
+                select {
+                    value: "",
+                    onchange: move |e| {
+                        if let Ok(kinds) = FormKind::parse_list(&e.value()) {
+                            let mut current = selected();
+                            current.extend(kinds.into_iter().filter(|k| !selected().contains(k)));
+                            selected.set(current);
                         }
+                    },
+                    option { value: "", "{placeholder}" }
+                    if !FormKind::REGIONAL.iter().all(|k| selected().contains(k)) {
+                        option { value: "regional", "Regional" }
+                    }
+                    {
+                        FormKind::ALL.iter()
+                            .filter(|kind| !selected().contains(kind))
+                            .map(|kind| {
+                                rsx! {
+                                    option { value: "{kind}", "{kind}" }
+                                }
+                            })
                     }
                 }
             }
@@ -602,9 +1203,21 @@ pub fn App() -> Element {
 
 // Create a separate component for each Pokemon row
 #[component]
-fn PokemonRow(pokemon: Pokemon) -> Element {
+fn PokemonRow(
+    pokemon: Pokemon,
+    is_pinned: bool,
+    can_pin: bool,
+    on_select: EventHandler<Pokemon>,
+    on_toggle_pin: EventHandler<Pokemon>,
+    on_hide: EventHandler<Pokemon>,
+) -> Element {
+    let clicked = pokemon.clone();
+    let pin_target = pokemon.clone();
+    let hide_target = pokemon.clone();
     rsx! {
-        tr { key: "{pokemon.name}",
+        tr { key: "{pokemon.id}",
+            class: "pokemon-row",
+            onclick: move |_| on_select.call(clicked.clone()),
             td { 
                 class: "pokemon-name",
                 "{pokemon.name}"
@@ -615,11 +1228,9 @@ fn PokemonRow(pokemon: Pokemon) -> Element {
                 }
             }
             td { class: "pokemon-type",
-                span { class: "type {pokemon.type1.to_lowercase()}", "{pokemon.type1}" }
-                if let Some(type2) = &pokemon.type2 {
-                    if !type2.is_empty() {
-                        span { class: "type {type2.to_lowercase()}", "{type2}" }
-                    }
+                span { class: "type {pokemon.type1.css_class()}", "{pokemon.type1}" }
+                if let Some(type2) = pokemon.type2 {
+                    span { class: "type {type2.css_class()}", "{type2}" }
                 }
             }
             td { "{pokemon.hp}" }
@@ -632,6 +1243,183 @@ fn PokemonRow(pokemon: Pokemon) -> Element {
             td { "{pokemon.height} m" }
             td { "{pokemon.weight} kg" }
             td { "{pokemon.generation}" }
+            td {
+                button {
+                    class: if is_pinned { "pin-button pinned" } else { "pin-button" },
+                    disabled: !is_pinned && !can_pin,
+                    onclick: move |e| {
+                        // Pinning shouldn't also open the detail panel
+                        e.stop_propagation();
+                        on_toggle_pin.call(pin_target.clone());
+                    },
+                    if is_pinned { "Unpin" } else { "Pin" }
+                }
+                button {
+                    class: "pin-button",
+                    onclick: move |e| {
+                        e.stop_propagation();
+                        on_hide.call(hide_target.clone());
+                    },
+                    "Hide"
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+// Profile of a single Pokémon, opened by clicking its row
+#[component]
+fn PokemonDetail(pokemon: Pokemon, on_close: EventHandler<()>) -> Element {
+    // 0 means the dataset didn't say, see `dataset::fill_dex_numbers`
+    let dex_number = match pokemon.dex_number {
+        0 => String::new(),
+        n => format!("#{:04} ", n),
+    };
+    rsx! {
+        div { class: "detail-panel",
+            div { class: "detail-header",
+                h3 {
+                    span { class: "dex-number", "{dex_number}" }
+                    "{pokemon.name}"
+                    if let Some(form) = &pokemon.form {
+                        span { class: "form", " ({form})" }
+                    }
+                }
+                button { class: "remove-tag detail-close", onclick: move |_| on_close.call(()), "×" }
+            }
+            div { class: "detail-types",
+                span { class: "type {pokemon.type1.css_class()}", "{pokemon.type1}" }
+                if let Some(type2) = pokemon.type2 {
+                    span { class: "type {type2.css_class()}", "{type2}" }
+                }
+            }
+            div { class: "detail-stats",
+                for (label, value) in pokemon.base_stats() {
+                    div { class: "stat-row",
+                        span { class: "stat-label", "{label}" }
+                        span { class: "stat-value", "{value}" }
+                        div { class: "stat-bar-track",
+                            div {
+                                class: "stat-bar",
+                                // Proportional to the highest possible base stat
+                                style: "width: {f32::from(value) / f32::from(Pokemon::MAX_STAT) * 100.0}%",
+                            }
+                        }
+                    }
+                }
+                div { class: "stat-row total",
+                    span { class: "stat-label", "Total" }
+                    span { class: "stat-value", "{pokemon.total}" }
+                }
+            }
+            div { class: "detail-info",
+                span { "Height: {pokemon.height} m" }
+                span { "Weight: {pokemon.weight} kg" }
+                span { "Generation: {pokemon.generation}" }
+                span { "Form: {pokemon.form_kind}" }
+            }
+        }
+    }
+}
+
+// (label, value getter, decimals, whether the highest value is highlighted)
+type ComparisonRow = (&'static str, fn(&Pokemon) -> f32, usize, bool);
+
+// Pinned Pokémon side by side, one column each. The best value of every stat is
+// highlighted and later columns show their difference from the first.
+#[component]
+fn ComparisonView(pinned: Signal<Vec<Pokemon>>) -> Element {
+    let entries = pinned();
+    let first = entries[0].clone();
+
+    let numeric_rows: [ComparisonRow; 10] = [
+        ("HP", |p| f32::from(p.hp), 0, true),
+        ("Attack", |p| f32::from(p.attack), 0, true),
+        ("Defense", |p| f32::from(p.defense), 0, true),
+        ("Sp. Atk", |p| f32::from(p.sp_atk), 0, true),
+        ("Sp. Def", |p| f32::from(p.sp_def), 0, true),
+        ("Speed", |p| f32::from(p.speed), 0, true),
+        ("Total", |p| f32::from(p.total), 0, true),
+        ("Height (m)", |p| p.height, 1, false),
+        ("Weight (kg)", |p| p.weight, 1, false),
+        ("Generation", |p| f32::from(p.generation), 0, false),
+    ];
+
+    rsx! {
+        div { class: "comparison-panel",
+            div { class: "detail-header",
+                h3 { "Comparison ({entries.len()}/{MAX_PINNED})" }
+                button { class: "reset-button", onclick: move |_| pinned.set(Vec::new()), "Clear" }
+            }
+            table { class: "pokemon-table comparison-table",
+                thead {
+                    tr {
+                        th { "" }
+                        for (i, pokemon) in entries.iter().enumerate() {
+                            th { key: "{pokemon.id}",
+                                "{pokemon.display_name()}"
+                                button {
+                                    class: "remove-tag",
+                                    onclick: move |_| {
+                                        let mut current = pinned();
+                                        current.remove(i);
+                                        pinned.set(current);
+                                    },
+                                    "×"
+                                }
+                            }
+                        }
+                    }
+                }
+                tbody {
+                    tr {
+                        td { class: "stat-label", "Form" }
+                        for pokemon in entries.iter() {
+                            td { {pokemon.form.clone().unwrap_or_default()} }
+                        }
+                    }
+                    tr {
+                        td { class: "stat-label", "Type" }
+                        for pokemon in entries.iter() {
+                            td {
+                                span { class: "type {pokemon.type1.css_class()}", "{pokemon.type1}" }
+                                if let Some(type2) = pokemon.type2 {
+                                    span { class: "type {type2.css_class()}", "{type2}" }
+                                }
+                            }
+                        }
+                    }
+                    for (label, value_of, decimals, highlight) in numeric_rows {
+                        {
+                            let best = entries.iter().map(value_of).fold(f32::MIN, f32::max);
+                            let base = value_of(&first);
+                            rsx! {
+                                tr {
+                                    td { class: "stat-label", "{label}" }
+                                    for (i, pokemon) in entries.iter().enumerate() {
+                                        {
+                                            let value = value_of(pokemon);
+                                            let diff = value - base;
+                                            rsx! {
+                                                td {
+                                                    class: if highlight && value == best && entries.len() > 1 { "best-value" },
+                                                    "{value:.decimals$}"
+                                                    if i > 0 && diff != 0.0 {
+                                                        span {
+                                                            class: if diff > 0.0 { "diff positive" } else { "diff negative" },
+                                                            " ({diff:+.decimals$})"
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
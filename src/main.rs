@@ -2,19 +2,25 @@
 // need dioxus
 use dioxus::prelude::*;
 use pokemon_filter::App;
-use std::fs;
-use pokemon_filter::pokemon::Pokemon;
 
-use components::{Hero, Echo};
+use components::Hero;
 
 /// Define a components module that contains all shared components for our app.
 mod components;
 
 // Add this line for CSS linking
+#[cfg(feature = "desktop")]
 const MAIN_CSS: &str = include_str!("../assets/styling/main.css");
 
+// The web, mobile and fullstack builds link the stylesheet as an asset instead
+#[cfg(not(feature = "desktop"))]
+const MAIN_CSS: Asset = asset!("/assets/styling/main.css");
+
+#[cfg(feature = "desktop")]
 fn main() {
-    LaunchBuilder::desktop()
+    use pokemon_filter::dataset::DatasetPath;
+
+    let mut builder = LaunchBuilder::desktop()
         .with_cfg(dioxus::desktop::Config::new()
             .with_window(dioxus::desktop::WindowBuilder::new()
                 .with_title("Pokémon Filter")
@@ -23,6 +29,26 @@ fn main() {
             .with_custom_head(format!(
                 r#"<style>{}</style>"#,
                 MAIN_CSS
-            )))
-        .launch(App);
+            )));
+
+    // `pokemon_filter path/to/pokedex.bin` loads that file instead of the built-in data
+    if let Some(path) = std::env::args_os().nth(1) {
+        builder = builder.with_context(DatasetPath(path.into()));
+    }
+
+    builder.launch(App);
+}
+
+#[cfg(not(feature = "desktop"))]
+fn main() {
+    dioxus::launch(Root);
+}
+
+#[cfg(not(feature = "desktop"))]
+#[component]
+fn Root() -> Element {
+    rsx! {
+        document::Stylesheet { href: MAIN_CSS }
+        App {}
+    }
 }
@@ -1,14 +1,25 @@
 use serde::{Deserialize, Serialize};
 use bincode::{Decode, Encode};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Serialize, Deserialize, Decode, Encode, Debug, Clone, PartialEq)]
 pub struct Pokemon {
+    // Unique per entry and stable across dataset updates, e.g. "charizard-mega-x",
+    // so it is the key for anything that refers to one Pokémon
+    pub id: String,
+    // National Pokédex number, shared by every form of a species. 0 when unknown,
+    // see `dataset::fill_dex_numbers`
+    pub dex_number: u16,
+    // What kind of form this entry is, worked out from `form` by the converter
+    pub form_kind: FormKind,
     // Match the exact order of columns in the CSV file header
     pub generation: u8,
     pub name: String,
     pub form: Option<String>,
-    pub type1: String,
-    pub type2: Option<String>,
+    pub type1: PokemonType,
+    pub type2: Option<PokemonType>,
     pub total: u16,
     pub hp: u8,
     pub attack: u8,
@@ -17,9 +28,402 @@ pub struct Pokemon {
     pub sp_atk: u8,
     #[serde(rename = "Sp. Def")]  // This matches the CSV header
     pub sp_def: u8,
-    pub speed: u8, 
+    pub speed: u8,
     pub height: f32,
     pub weight: f32,
 }
 
+impl Pokemon {
+    /// Highest value any single base stat can take.
+    pub const MAX_STAT: u8 = 255;
 
+    /// Newest generation the dataset is expected to cover.
+    pub const MAX_GENERATION: u8 = 9;
+
+    /// Name with the form in brackets, e.g. "Castform (Sunny Form)".
+    pub fn display_name(&self) -> String {
+        match &self.form {
+            Some(form) => format!("{} ({})", self.name, form),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Builds the `id` for a name and form: lowercase words joined by dashes, with
+    /// the species name left out of the form part. "Charizard" + "Mega Charizard X"
+    /// gives "charizard-mega-x" and "Castform" + "Sunny Form" gives "castform-sunny-form".
+    pub fn make_id(name: &str, form: Option<&str>) -> String {
+        let name_words = slug_words(name);
+        let mut words = name_words.clone();
+        if let Some(form) = form {
+            words.extend(slug_words(form).into_iter().filter(|w| !name_words.contains(w)));
+        }
+        words.join("-")
+    }
+
+    /// The six base stats with their display labels, in the order the games list them.
+    pub fn base_stats(&self) -> [(&'static str, u8); 6] {
+        [
+            ("HP", self.hp),
+            ("Attack", self.attack),
+            ("Defense", self.defense),
+            ("Sp. Atk", self.sp_atk),
+            ("Sp. Def", self.sp_def),
+            ("Speed", self.speed),
+        ]
+    }
+}
+
+/// Promotes the first listed form of each species that has no formless entry to
+/// `FormKind::Base`, so "Deoxys (Normal Forme)" and "Meowstic (Male)" count as the
+/// base form while "Meowstic (Female)" stays a gender form. Mega, regional and
+/// Gigantamax forms are never promoted. Run after `dex_number` is filled in;
+/// entries without one are grouped by name instead.
+pub fn assign_base_forms(pokemon: &mut [Pokemon]) {
+    let has_base: HashSet<(u16, &str)> = pokemon.iter()
+        .filter(|p| p.form_kind == FormKind::Base)
+        .map(species_key)
+        .collect();
+    let promote: Vec<bool> = {
+        let mut promoted = HashSet::new();
+        pokemon.iter()
+            .map(|p| {
+                let promotable = matches!(p.form_kind, FormKind::Gender | FormKind::Alternate);
+                promotable && !has_base.contains(&species_key(p)) && promoted.insert(species_key(p))
+            })
+            .collect()
+    };
+    for (p, promote) in pokemon.iter_mut().zip(promote) {
+        if promote {
+            p.form_kind = FormKind::Base;
+        }
+    }
+}
+
+// The dex number when known, otherwise the name
+fn species_key(p: &Pokemon) -> (u16, &str) {
+    match p.dex_number {
+        0 => (0, p.name.as_str()),
+        n => (n, ""),
+    }
+}
+
+// "Mr. Mime" -> ["mr", "mime"]. The gender symbols are spelled out so "Nidoran♀"
+// gets the same ID as "Nidoran" + "Female" rather than colliding with "Nidoran♂".
+fn slug_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace('♀', " female ")
+        .replace('♂', " male ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// The 18 elemental types, in the order the games list them.
+#[derive(Serialize, Deserialize, Decode, Encode, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PokemonType {
+    Normal,
+    Fire,
+    Water,
+    Electric,
+    Grass,
+    Ice,
+    Fighting,
+    Poison,
+    Ground,
+    Flying,
+    Psychic,
+    Bug,
+    Rock,
+    Ghost,
+    Dragon,
+    Dark,
+    Steel,
+    Fairy,
+}
+
+impl PokemonType {
+    pub const ALL: [PokemonType; 18] = [
+        PokemonType::Normal, PokemonType::Fire, PokemonType::Water, PokemonType::Electric,
+        PokemonType::Grass, PokemonType::Ice, PokemonType::Fighting, PokemonType::Poison,
+        PokemonType::Ground, PokemonType::Flying, PokemonType::Psychic, PokemonType::Bug,
+        PokemonType::Rock, PokemonType::Ghost, PokemonType::Dragon, PokemonType::Dark,
+        PokemonType::Steel, PokemonType::Fairy,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PokemonType::Normal => "Normal",
+            PokemonType::Fire => "Fire",
+            PokemonType::Water => "Water",
+            PokemonType::Electric => "Electric",
+            PokemonType::Grass => "Grass",
+            PokemonType::Ice => "Ice",
+            PokemonType::Fighting => "Fighting",
+            PokemonType::Poison => "Poison",
+            PokemonType::Ground => "Ground",
+            PokemonType::Flying => "Flying",
+            PokemonType::Psychic => "Psychic",
+            PokemonType::Bug => "Bug",
+            PokemonType::Rock => "Rock",
+            PokemonType::Ghost => "Ghost",
+            PokemonType::Dragon => "Dragon",
+            PokemonType::Dark => "Dark",
+            PokemonType::Steel => "Steel",
+            PokemonType::Fairy => "Fairy",
+        }
+    }
+
+    /// Lowercase name, which is also the CSS class carrying the type colour.
+    pub fn css_class(self) -> String {
+        self.name().to_lowercase()
+    }
+}
+
+impl fmt::Display for PokemonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseTypeError(pub String);
+
+impl fmt::Display for ParseTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown Pokémon type '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseTypeError {}
+
+impl FromStr for PokemonType {
+    type Err = ParseTypeError;
+
+    // Case-insensitive so user input like "fire" works as well as the CSV's "Fire"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        PokemonType::ALL.iter()
+            .copied()
+            .find(|t| t.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseTypeError(s.to_string()))
+    }
+}
+
+/// Broad category of a form, for rules like "no Megas" or "regional variants only".
+#[derive(Serialize, Deserialize, Decode, Encode, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FormKind {
+    // The species' standard form, see `assign_base_forms`
+    Base,
+    // Mega Evolutions
+    Mega,
+    Alolan,
+    Galarian,
+    Hisuian,
+    Paldean,
+    Gigantamax,
+    // Male or female form of a species whose genders differ in stats or appearance
+    Gender,
+    // Any other alternate form, e.g. "Castform (Sunny Form)" or "Kyogre (Primal Kyogre)"
+    Alternate,
+}
+
+impl FormKind {
+    pub const ALL: [FormKind; 9] = [
+        FormKind::Base, FormKind::Mega, FormKind::Alolan, FormKind::Galarian, FormKind::Hisuian,
+        FormKind::Paldean, FormKind::Gigantamax, FormKind::Gender, FormKind::Alternate,
+    ];
+
+    /// The four regional variant kinds.
+    pub const REGIONAL: [FormKind; 4] = [FormKind::Alolan, FormKind::Galarian, FormKind::Hisuian, FormKind::Paldean];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FormKind::Base => "Base",
+            FormKind::Mega => "Mega",
+            FormKind::Alolan => "Alolan",
+            FormKind::Galarian => "Galarian",
+            FormKind::Hisuian => "Hisuian",
+            FormKind::Paldean => "Paldean",
+            FormKind::Gigantamax => "Gigantamax",
+            FormKind::Gender => "Gender",
+            FormKind::Alternate => "Alternate",
+        }
+    }
+
+    pub fn is_regional(self) -> bool {
+        FormKind::REGIONAL.contains(&self)
+    }
+
+    /// Classifies the free-text form of a single entry. Entries with no form are
+    /// `Base`; the first form of a species without one only becomes `Base` once
+    /// the whole dataset is known, in `assign_base_forms`.
+    pub fn classify(form: Option<&str>) -> FormKind {
+        let Some(form) = form else {
+            return FormKind::Base;
+        };
+        let first_word = form.split_whitespace().next().unwrap_or("");
+        match first_word {
+            "Mega" => FormKind::Mega,
+            "Alolan" => FormKind::Alolan,
+            "Galarian" => FormKind::Galarian,
+            "Hisuian" => FormKind::Hisuian,
+            "Paldean" => FormKind::Paldean,
+            "Gigantamax" => FormKind::Gigantamax,
+            "Male" | "Female" if first_word == form => FormKind::Gender,
+            // Paldean Tauros is listed by breed, e.g. "Combat Breed"
+            _ if form.ends_with(" Breed") => FormKind::Paldean,
+            _ => FormKind::Alternate,
+        }
+    }
+
+    /// Parses a comma-separated list of kinds, where "regional" stands for all four
+    /// regional kinds. Case-insensitive, e.g. "base,regional".
+    pub fn parse_list(s: &str) -> Result<Vec<FormKind>, ParseFormKindError> {
+        let mut kinds = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let parsed = if part.eq_ignore_ascii_case("regional") {
+                FormKind::REGIONAL.to_vec()
+            } else {
+                vec![part.parse()?]
+            };
+            for kind in parsed {
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+            }
+        }
+        Ok(kinds)
+    }
+}
+
+impl fmt::Display for FormKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFormKindError(pub String);
+
+impl fmt::Display for ParseFormKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown form kind '{}' (expected base, mega, alolan, galarian, hisuian, paldean, \
+             regional, gigantamax, gender or alternate)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseFormKindError {}
+
+impl FromStr for FormKind {
+    type Err = ParseFormKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        FormKind::ALL.iter()
+            .copied()
+            .find(|k| k.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseFormKindError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{pokedex, pokemon};
+
+    #[test]
+    fn ids_drop_punctuation_and_keep_accents() {
+        assert_eq!(Pokemon::make_id("Mr. Mime", None), "mr-mime");
+        assert_eq!(Pokemon::make_id("Mr. Mime", Some("Galarian Mr. Mime")), "mr-mime-galarian");
+        assert_eq!(Pokemon::make_id("Farfetch'd", None), "farfetch-d");
+        assert_eq!(Pokemon::make_id("Flabébé", None), "flabébé");
+        assert_eq!(Pokemon::make_id("Castform", Some("Sunny Form")), "castform-sunny-form");
+    }
+
+    #[test]
+    fn nidoran_ids_split_on_gender_however_it_is_written() {
+        assert_eq!(Pokemon::make_id("Nidoran", Some("Female")), "nidoran-female");
+        assert_eq!(Pokemon::make_id("Nidoran♀", None), "nidoran-female");
+        assert_eq!(Pokemon::make_id("Nidoran♂", None), "nidoran-male");
+        assert_ne!(pokemon("nidoran-female").dex_number, pokemon("nidoran-male").dex_number);
+    }
+
+    #[test]
+    fn mega_and_base_ids_differ_and_every_id_is_unique() {
+        assert_eq!(Pokemon::make_id("Charizard", None), "charizard");
+        assert_eq!(Pokemon::make_id("Charizard", Some("Mega Charizard X")), "charizard-mega-x");
+        assert_eq!(Pokemon::make_id("Charizard", Some("Mega Charizard Y")), "charizard-mega-y");
+
+        let pokemon = pokedex();
+        let ids: HashSet<&str> = pokemon.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids.len(), pokemon.len());
+    }
+
+    #[test]
+    fn the_first_form_of_a_species_without_a_formless_entry_is_the_base() {
+        assert_eq!(pokemon("meowstic-male").form_kind, FormKind::Base);
+        assert_eq!(pokemon("meowstic-female").form_kind, FormKind::Gender);
+        assert_eq!(pokemon("rotom-wash").form_kind, FormKind::Alternate);
+        assert_eq!(pokemon("charizard-mega-x").form_kind, FormKind::Mega);
+    }
+
+    #[test]
+    fn base_forms_are_grouped_by_name_without_dex_numbers_and_megas_never_promoted() {
+        let mut pokemon: Vec<Pokemon> = pokedex().into_iter()
+            .filter(|p| p.name != "Charizard" || p.form.is_some())
+            .map(|p| Pokemon { dex_number: 0, form_kind: FormKind::classify(p.form.as_deref()), ..p })
+            .collect();
+        assign_base_forms(&mut pokemon);
+
+        let kind = |id: &str| pokemon.iter().find(|p| p.id == id).unwrap().form_kind;
+        assert_eq!(kind("nidoran-female"), FormKind::Base);
+        // Grouped by name, the Nidoran are one species
+        assert_eq!(kind("nidoran-male"), FormKind::Gender);
+        assert_eq!(kind("meowstic-male"), FormKind::Base);
+        assert_eq!(kind("charizard-mega-x"), FormKind::Mega);
+    }
+
+    #[test]
+    fn forms_are_classified_by_their_first_word() {
+        let cases = [
+            (None, FormKind::Base),
+            (Some("Mega Charizard X"), FormKind::Mega),
+            (Some("Alolan Raichu"), FormKind::Alolan),
+            (Some("Galarian Mr. Mime"), FormKind::Galarian),
+            (Some("Hisuian Growlithe"), FormKind::Hisuian),
+            (Some("Paldean Wooper"), FormKind::Paldean),
+            (Some("Gigantamax Pikachu"), FormKind::Gigantamax),
+            (Some("Female"), FormKind::Gender),
+            (Some("Male"), FormKind::Gender),
+            (Some("Sunny Form"), FormKind::Alternate),
+            // Only a form that is just the gender counts as a gender form
+            (Some("Female Rider"), FormKind::Alternate),
+            (Some("Combat Breed"), FormKind::Paldean),
+            (Some("Aqua Breed"), FormKind::Paldean),
+            // The prefix has to be a whole word
+            (Some("Megaton"), FormKind::Alternate),
+        ];
+        for (form, kind) in cases {
+            assert_eq!(FormKind::classify(form), kind, "{:?}", form);
+        }
+    }
+
+    #[test]
+    fn kind_lists_expand_regional_and_skip_repeats() {
+        assert_eq!(FormKind::parse_list("regional"), Ok(FormKind::REGIONAL.to_vec()));
+        assert_eq!(
+            FormKind::parse_list(" Base, ALOLAN,regional ,,"),
+            Ok(vec![FormKind::Base, FormKind::Alolan, FormKind::Galarian, FormKind::Hisuian, FormKind::Paldean])
+        );
+        assert_eq!(FormKind::parse_list(""), Ok(Vec::new()));
+        assert_eq!(FormKind::parse_list("mega,shiny"), Err(ParseFormKindError("shiny".to_string())));
+        for kind in FormKind::ALL {
+            assert_eq!(kind.name().parse::<FormKind>(), Ok(kind));
+        }
+    }
+}
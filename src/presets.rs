@@ -0,0 +1,223 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::filter::FilterCriteria;
+
+/// A saved filter configuration, e.g. "Gen 1–4 fast special attackers".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub criteria: FilterCriteria,
+}
+
+/// Every saved preset, stored as one JSON file in the user config directory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PresetStore {
+    pub presets: Vec<Preset>,
+}
+
+#[derive(Debug)]
+pub enum PresetError {
+    NoConfigDir,
+    EmptyName,
+    NotFound(String),
+    DuplicateName(String),
+    Io(PathBuf, std::io::Error),
+    Json(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::NoConfigDir => write!(f, "no config directory to store presets in"),
+            PresetError::EmptyName => write!(f, "preset name can't be empty"),
+            PresetError::NotFound(name) => write!(f, "no preset named '{}'", name),
+            PresetError::DuplicateName(name) => write!(f, "a preset named '{}' already exists", name),
+            PresetError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            PresetError::Json(path, e) => write!(f, "{} is not a valid preset file: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+impl PresetStore {
+    /// `<config dir>/pokemon_filter/presets.json`, e.g. `~/.config/pokemon_filter/presets.json`.
+    pub fn default_path() -> Result<PathBuf, PresetError> {
+        dirs::config_dir()
+            .map(|dir| dir.join("pokemon_filter").join("presets.json"))
+            .ok_or(PresetError::NoConfigDir)
+    }
+
+    /// Reads the store, treating a missing file as having no presets yet.
+    pub fn load(path: &Path) -> Result<Self, PresetError> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| PresetError::Json(path.to_path_buf(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(PresetError::Io(path.to_path_buf(), e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PresetError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| PresetError::Io(dir.to_path_buf(), e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| PresetError::Json(path.to_path_buf(), e))?;
+        fs::write(path, json).map_err(|e| PresetError::Io(path.to_path_buf(), e))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    /// Saves the criteria under the name, replacing a preset of the same name.
+    pub fn upsert(&mut self, name: &str, criteria: FilterCriteria) -> Result<(), PresetError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PresetError::EmptyName);
+        }
+        match self.presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => existing.criteria = criteria,
+            None => self.presets.push(Preset { name: name.to_string(), criteria }),
+        }
+        Ok(())
+    }
+
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<(), PresetError> {
+        let new = new.trim();
+        if new.is_empty() {
+            return Err(PresetError::EmptyName);
+        }
+        if new != old && self.get(new).is_some() {
+            return Err(PresetError::DuplicateName(new.to_string()));
+        }
+        let preset = self.presets.iter_mut()
+            .find(|p| p.name == old)
+            .ok_or_else(|| PresetError::NotFound(old.to_string()))?;
+        preset.name = new.to_string();
+        Ok(())
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<(), PresetError> {
+        let before = self.presets.len();
+        self.presets.retain(|p| p.name != name);
+        if self.presets.len() == before {
+            return Err(PresetError::NotFound(name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pokemon::{FormKind, PokemonType};
+    use crate::test_data::temp_dir;
+
+    fn criteria(min_speed: u8) -> FilterCriteria {
+        FilterCriteria { min_speed, ..FilterCriteria::default() }
+    }
+
+    fn store(names: &[&str]) -> PresetStore {
+        let mut store = PresetStore::default();
+        for (i, name) in names.iter().enumerate() {
+            store.upsert(name, criteria(i as u8)).unwrap();
+        }
+        store
+    }
+
+    fn names(store: &PresetStore) -> Vec<&str> {
+        store.presets.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn a_missing_file_is_an_empty_store() {
+        let dir = temp_dir("presets-missing");
+        assert_eq!(PresetStore::load(&dir.join("presets.json")).unwrap(), PresetStore::default());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_corrupt_file_is_an_error() {
+        let dir = temp_dir("presets-corrupt");
+        let path = dir.join("presets.json");
+        fs::write(&path, "{\"presets\": [").unwrap();
+
+        let error = PresetStore::load(&path).unwrap_err();
+        assert!(matches!(error, PresetError::Json(..)));
+        assert!(error.to_string().starts_with(&format!("{} is not a valid preset file: ", path.display())));
+        // Loading alone never touches the file
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"presets\": [");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn save_then_load_round_trips_and_creates_the_directory() {
+        let dir = temp_dir("presets-round-trip");
+        let path = dir.join("pokemon_filter").join("presets.json");
+        let mut store = store(&["Fast", "Bulky"]);
+        store.upsert("Megas", FilterCriteria {
+            form_kinds: vec![FormKind::Mega],
+            excluded_types: vec![PokemonType::Fairy],
+            excluded_pokemon: vec!["charizard-mega-y".to_string()],
+            max_height: f32::MAX,
+            ..FilterCriteria::default()
+        }).unwrap();
+
+        store.save(&path).unwrap();
+        assert_eq!(PresetStore::load(&path).unwrap(), store);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn presets_saved_before_form_kinds_still_load() {
+        let dir = temp_dir("presets-old");
+        let path = dir.join("presets.json");
+        let mut json = serde_json::to_value(store(&["Old"])).unwrap();
+        let criteria = json["presets"][0]["criteria"].as_object_mut().unwrap();
+        criteria.remove("form_kinds");
+        criteria.remove("excluded_form_kinds");
+        fs::write(&path, json.to_string()).unwrap();
+
+        assert_eq!(PresetStore::load(&path).unwrap(), store(&["Old"]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn upsert_replaces_a_preset_of_the_same_name() {
+        let mut store = store(&["Fast", "Bulky"]);
+        store.upsert("  Fast ", criteria(120)).unwrap();
+        assert_eq!(names(&store), ["Fast", "Bulky"]);
+        assert_eq!(store.get("Fast").unwrap().criteria, criteria(120));
+
+        assert!(matches!(store.upsert("   ", criteria(0)), Err(PresetError::EmptyName)));
+    }
+
+    #[test]
+    fn rename_rejects_a_name_in_use() {
+        let mut store = store(&["Fast", "Bulky"]);
+        let error = store.rename("Fast", "Bulky").unwrap_err();
+        assert_eq!(error.to_string(), "a preset named 'Bulky' already exists");
+        assert_eq!(names(&store), ["Fast", "Bulky"]);
+
+        // Renaming to its own name is allowed, the rest of the preset is kept
+        store.rename("Fast", "Fast").unwrap();
+        store.rename("Fast", " Speedy ").unwrap();
+        assert_eq!(names(&store), ["Speedy", "Bulky"]);
+        assert_eq!(store.get("Speedy").unwrap().criteria, criteria(0));
+
+        assert_eq!(store.rename("Gone", "New").unwrap_err().to_string(), "no preset named 'Gone'");
+        assert!(matches!(store.rename("Bulky", ""), Err(PresetError::EmptyName)));
+    }
+
+    #[test]
+    fn delete_of_an_unknown_name_is_an_error() {
+        let mut store = store(&["Fast", "Bulky"]);
+        assert_eq!(store.delete("Gone").unwrap_err().to_string(), "no preset named 'Gone'");
+        assert_eq!(names(&store), ["Fast", "Bulky"]);
+
+        store.delete("Fast").unwrap();
+        assert_eq!(names(&store), ["Bulky"]);
+    }
+}
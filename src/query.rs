@@ -0,0 +1,416 @@
+use std::fmt;
+use crate::filter::{FilterCriteria, MAX_TOTAL};
+use crate::pokemon::{FormKind, Pokemon, PokemonType};
+
+/// Parses a search query such as `type:fire gen:1-3 spe>=100 bst<500 -type:flying -form:mega name:char*`
+/// into the same criteria the filter panel drives.
+///
+/// Terms are separated by whitespace and all of them must hold. Words without a
+/// field are matched against the name. Values containing spaces can be quoted,
+/// e.g. `name:"mr. mime"`. A leading `-` negates a `type:` or `form:` term.
+/// Several `form:` terms allow any of their kinds, e.g. `form:base form:regional`.
+pub fn parse_query(input: &str) -> Result<FilterCriteria, QueryError> {
+    let mut criteria = FilterCriteria::default();
+    let mut name_words = Vec::new();
+
+    for (column, token) in tokenize(input)? {
+        let err = |message: String| QueryError { column, message };
+        let (negated, term) = match token.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, token.as_str()),
+        };
+
+        let Some((key, op, value)) = split_term(term) else {
+            if negated {
+                return Err(err(format!("'-{}' can't be negated, only type: and form: terms can", term)));
+            }
+            name_words.push(term.to_string());
+            continue;
+        };
+        let key = key.to_lowercase();
+        if value.is_empty() {
+            return Err(err(format!("missing value after '{}{}'", key, op.as_str())));
+        }
+        if negated && !matches!(key.as_str(), "type" | "t" | "form") {
+            return Err(err(format!("'-{}' can't be negated, only type: and form: terms can", key)));
+        }
+
+        match key.as_str() {
+            "name" | "n" => {
+                expect_match_op(op, &key).map_err(err)?;
+                name_words.push(value.to_string());
+            }
+            "type" | "t" => {
+                expect_match_op(op, &key).map_err(err)?;
+                let t = parse_type(value).map_err(err)?;
+                if negated {
+                    criteria.excluded_types.push(t);
+                } else {
+                    criteria.has_types.push(t);
+                }
+            }
+            "form" => {
+                expect_match_op(op, &key).map_err(err)?;
+                let kinds = FormKind::parse_list(value).map_err(|e| err(e.to_string()))?;
+                let target = if negated { &mut criteria.excluded_form_kinds } else { &mut criteria.form_kinds };
+                for kind in kinds {
+                    if !target.contains(&kind) {
+                        target.push(kind);
+                    }
+                }
+            }
+            "type1" => {
+                expect_match_op(op, &key).map_err(err)?;
+                criteria.type1 = Some(parse_type(value).map_err(err)?);
+            }
+            "type2" => {
+                expect_match_op(op, &key).map_err(err)?;
+                criteria.type2 = Some(parse_type(value).map_err(err)?);
+            }
+            "weak" => {
+                expect_match_op(op, &key).map_err(err)?;
+                criteria.weak_to.push(parse_type(value).map_err(err)?);
+            }
+            "resists" | "resist" => {
+                expect_match_op(op, &key).map_err(err)?;
+                criteria.resists.push(parse_type(value).map_err(err)?);
+            }
+            "immune" => {
+                expect_match_op(op, &key).map_err(err)?;
+                criteria.immune_to.push(parse_type(value).map_err(err)?);
+            }
+            "gen" | "generation" => {
+                let bounds = int_bounds(op, value, Pokemon::MAX_GENERATION.into()).map_err(err)?;
+                narrow(&mut criteria.min_gen, &mut criteria.max_gen, bounds);
+            }
+            "hp" => {
+                let bounds = int_bounds(op, value, u8::MAX.into()).map_err(err)?;
+                narrow(&mut criteria.min_hp, &mut criteria.max_hp, bounds);
+            }
+            "atk" | "attack" => {
+                let bounds = int_bounds(op, value, u8::MAX.into()).map_err(err)?;
+                narrow(&mut criteria.min_attack, &mut criteria.max_attack, bounds);
+            }
+            "def" | "defense" => {
+                let bounds = int_bounds(op, value, u8::MAX.into()).map_err(err)?;
+                narrow(&mut criteria.min_defense, &mut criteria.max_defense, bounds);
+            }
+            "spa" | "spatk" | "sp_atk" => {
+                let bounds = int_bounds(op, value, u8::MAX.into()).map_err(err)?;
+                narrow(&mut criteria.min_sp_atk, &mut criteria.max_sp_atk, bounds);
+            }
+            "spd" | "spdef" | "sp_def" => {
+                let bounds = int_bounds(op, value, u8::MAX.into()).map_err(err)?;
+                narrow(&mut criteria.min_sp_def, &mut criteria.max_sp_def, bounds);
+            }
+            "spe" | "speed" => {
+                let bounds = int_bounds(op, value, u8::MAX.into()).map_err(err)?;
+                narrow(&mut criteria.min_speed, &mut criteria.max_speed, bounds);
+            }
+            "bst" | "total" => {
+                let bounds = int_bounds(op, value, MAX_TOTAL.into()).map_err(err)?;
+                narrow(&mut criteria.min_total, &mut criteria.max_total, bounds);
+            }
+            "height" | "ht" => {
+                let (lo, hi) = float_bounds(op, value).map_err(err)?;
+                criteria.min_height = criteria.min_height.max(lo);
+                criteria.max_height = criteria.max_height.min(hi);
+            }
+            "weight" | "wt" => {
+                let (lo, hi) = float_bounds(op, value).map_err(err)?;
+                criteria.min_weight = criteria.min_weight.max(lo);
+                criteria.max_weight = criteria.max_weight.min(hi);
+            }
+            _ => {
+                return Err(err(format!(
+                    "unknown field '{}' (expected name, type, type1, type2, form, weak, resists, immune, \
+                     gen, hp, atk, def, spa, spd, spe, bst, height or weight)",
+                    key
+                )));
+            }
+        }
+    }
+
+    criteria.name = name_words.join(" ");
+    Ok(criteria)
+}
+
+/// Why a query was rejected, pointing at the offending term.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    // 1-based character column where the term starts
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Match,
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    fn as_str(self) -> &'static str {
+        match self {
+            Op::Match => ":",
+            Op::Eq => "=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+        }
+    }
+}
+
+// Splits on whitespace outside double quotes, returning each term with its column
+fn tokenize(input: &str) -> Result<Vec<(usize, String)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = None;
+    let mut quote_start = None;
+
+    for (column, c) in input.chars().enumerate().map(|(i, c)| (i + 1, c)) {
+        if c == '"' {
+            quote_start = match quote_start {
+                Some(_) => None,
+                None => Some(column),
+            };
+            start.get_or_insert(column);
+        } else if c.is_whitespace() && quote_start.is_none() {
+            if let Some(s) = start.take() {
+                tokens.push((s, std::mem::take(&mut current)));
+            }
+        } else {
+            current.push(c);
+            start.get_or_insert(column);
+        }
+    }
+
+    if let Some(column) = quote_start {
+        return Err(QueryError { column, message: "unterminated quote".to_string() });
+    }
+    if let Some(s) = start {
+        tokens.push((s, current));
+    }
+    Ok(tokens)
+}
+
+// "spe>=100" -> ("spe", Ge, "100"); None for a bare word
+fn split_term(term: &str) -> Option<(&str, Op, &str)> {
+    let i = term.find([':', '=', '<', '>'])?;
+    let (key, rest) = term.split_at(i);
+    let (op, len) = if rest.starts_with(">=") {
+        (Op::Ge, 2)
+    } else if rest.starts_with("<=") {
+        (Op::Le, 2)
+    } else {
+        match rest.as_bytes()[0] {
+            b':' => (Op::Match, 1),
+            b'=' => (Op::Eq, 1),
+            b'>' => (Op::Gt, 1),
+            _ => (Op::Lt, 1),
+        }
+    };
+    Some((key, op, &rest[len..]))
+}
+
+fn expect_match_op(op: Op, key: &str) -> Result<(), String> {
+    match op {
+        Op::Match | Op::Eq => Ok(()),
+        _ => Err(format!("'{}' only supports '{}:value'", key, key)),
+    }
+}
+
+fn parse_type(value: &str) -> Result<PokemonType, String> {
+    value.parse::<PokemonType>().map_err(|e| e.to_string())
+}
+
+// Inclusive bounds for an integer field; `:` and `=` also accept a "min-max" range
+fn int_bounds(op: Op, value: &str, limit: u32) -> Result<(u32, u32), String> {
+    let number = |s: &str| -> Result<u32, String> {
+        let n = s.trim().parse::<u32>().map_err(|_| format!("'{}' is not a whole number", s))?;
+        if n > limit {
+            return Err(format!("{} is above the maximum of {}", n, limit));
+        }
+        Ok(n)
+    };
+
+    match op {
+        Op::Match | Op::Eq => match value.split_once('-') {
+            Some((lo, hi)) => {
+                let (lo, hi) = (number(lo)?, number(hi)?);
+                if lo > hi {
+                    return Err(format!("range {}-{} is backwards", lo, hi));
+                }
+                Ok((lo, hi))
+            }
+            None => {
+                let n = number(value)?;
+                Ok((n, n))
+            }
+        },
+        Op::Ge => Ok((number(value)?, limit)),
+        Op::Le => Ok((0, number(value)?)),
+        Op::Gt => {
+            let n = number(value)?;
+            if n == limit {
+                return Err(format!("nothing can be above the maximum of {}", limit));
+            }
+            Ok((n + 1, limit))
+        }
+        Op::Lt => {
+            let n = number(value)?;
+            if n == 0 {
+                return Err("nothing can be below 0".to_string());
+            }
+            Ok((0, n - 1))
+        }
+    }
+}
+
+// Inclusive bounds for height or weight
+fn float_bounds(op: Op, value: &str) -> Result<(f32, f32), String> {
+    let number = |s: &str| -> Result<f32, String> {
+        s.trim().parse::<f32>()
+            .ok()
+            .filter(|n| n.is_finite() && *n >= 0.0)
+            .ok_or_else(|| format!("'{}' is not a valid number", s))
+    };
+
+    match op {
+        Op::Match | Op::Eq => match value.split_once('-') {
+            Some((lo, hi)) => {
+                let (lo, hi) = (number(lo)?, number(hi)?);
+                if lo > hi {
+                    return Err(format!("range {}-{} is backwards", lo, hi));
+                }
+                Ok((lo, hi))
+            }
+            None => {
+                let n = number(value)?;
+                Ok((n, n))
+            }
+        },
+        Op::Ge => Ok((number(value)?, f32::MAX)),
+        Op::Le => Ok((0.0, number(value)?)),
+        Op::Gt => Ok((number(value)?.next_up(), f32::MAX)),
+        Op::Lt => Ok((0.0, number(value)?.next_down())),
+    }
+}
+
+// Intersects the field's current range with the parsed bounds
+fn narrow<T: Ord + Copy + TryFrom<u32>>(min: &mut T, max: &mut T, (lo, hi): (u32, u32)) {
+    if let Ok(lo) = T::try_from(lo) {
+        *min = (*min).max(lo);
+    }
+    if let Ok(hi) = T::try_from(hi) {
+        *max = (*max).min(hi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PokemonType::*;
+
+    fn error(input: &str) -> QueryError {
+        parse_query(input).expect_err(input)
+    }
+
+    #[test]
+    fn empty_query_is_the_default_criteria() {
+        assert_eq!(parse_query("   "), Ok(FilterCriteria::default()));
+    }
+
+    #[test]
+    fn bare_words_and_name_terms_join_into_the_name() {
+        let c = parse_query(r#"mr name:"mime jr""#).unwrap();
+        assert_eq!(c.name, "mr mime jr");
+    }
+
+    #[test]
+    fn type_terms_and_negation() {
+        let c = parse_query("type:fire t:Flying -type:water type1:fire type2=flying").unwrap();
+        assert_eq!(c.has_types, [Fire, Flying]);
+        assert_eq!(c.excluded_types, [Water]);
+        assert_eq!(c.type1, Some(Fire));
+        assert_eq!(c.type2, Some(Flying));
+    }
+
+    #[test]
+    fn form_terms_expand_regional_and_negate() {
+        let c = parse_query("form:base form:regional -form:mega").unwrap();
+        assert_eq!(
+            c.form_kinds,
+            [FormKind::Base, FormKind::Alolan, FormKind::Galarian, FormKind::Hisuian, FormKind::Paldean]
+        );
+        assert_eq!(c.excluded_form_kinds, [FormKind::Mega]);
+    }
+
+    #[test]
+    fn comparisons_set_inclusive_bounds() {
+        let c = parse_query("spe>=100 atk>99 def<50 hp<=80 bst=500-600 gen:3").unwrap();
+        assert_eq!((c.min_speed, c.max_speed), (100, 255));
+        assert_eq!((c.min_attack, c.max_attack), (100, 255));
+        assert_eq!((c.min_defense, c.max_defense), (0, 49));
+        assert_eq!((c.min_hp, c.max_hp), (0, 80));
+        assert_eq!((c.min_total, c.max_total), (500, 600));
+        assert_eq!((c.min_gen, c.max_gen), (3, 3));
+
+        let c = parse_query("gen>7").unwrap();
+        assert_eq!((c.min_gen, c.max_gen), (8, Pokemon::MAX_GENERATION));
+    }
+
+    #[test]
+    fn repeated_bounds_intersect() {
+        let c = parse_query("spe>=50 spe<=120 spe:100-200").unwrap();
+        assert_eq!((c.min_speed, c.max_speed), (100, 120));
+    }
+
+    #[test]
+    fn float_bounds_exclude_the_value_for_strict_comparisons() {
+        let c = parse_query("height>2 weight<=100.5").unwrap();
+        assert!(c.min_height > 2.0 && c.min_height < 2.001);
+        assert_eq!(c.max_height, f32::MAX);
+        assert_eq!((c.min_weight, c.max_weight), (0.0, 100.5));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_term() {
+        assert_eq!(error("fire typo:x"), QueryError {
+            column: 6,
+            message: "unknown field 'typo' (expected name, type, type1, type2, form, weak, resists, immune, \
+                      gen, hp, atk, def, spa, spd, spe, bst, height or weight)".to_string(),
+        });
+        assert_eq!(error("type:fyre").to_string(), "column 1: unknown Pokémon type 'fyre'");
+        assert_eq!(error("  spe>300").to_string(), "column 3: 300 is above the maximum of 255");
+        assert_eq!(error("spe:90-50").message, "range 90-50 is backwards");
+        assert_eq!(error("spe>255").message, "nothing can be above the maximum of 255");
+        assert_eq!(error("gen:12").message, "12 is above the maximum of 9");
+        assert_eq!(error("gen:8-10").message, "10 is above the maximum of 9");
+        assert_eq!(error("gen>9").message, "nothing can be above the maximum of 9");
+        assert_eq!(error("hp<0").message, "nothing can be below 0");
+        assert_eq!(error("hp:").message, "missing value after 'hp:'");
+        assert_eq!(error("type>fire").message, "'type' only supports 'type:value'");
+        assert_eq!(error("-gen:1").message, "'-gen' can't be negated, only type: and form: terms can");
+        assert_eq!(error("-fire").message, "'-fire' can't be negated, only type: and form: terms can");
+        assert_eq!(error("height:tall").message, "'tall' is not a valid number");
+    }
+
+    #[test]
+    fn unterminated_quote_points_at_the_quote() {
+        assert_eq!(error(r#"type:fire name:"mr"#), QueryError { column: 16, message: "unterminated quote".to_string() });
+    }
+}
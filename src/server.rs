@@ -0,0 +1,66 @@
+use dioxus_fullstack::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::filter::FilterCriteria;
+use crate::pokemon::{Pokemon, PokemonType};
+
+/// What a client needs to know about the server's dataset to set up its filters,
+/// without downloading the Pokémon themselves.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PokedexInfo {
+    pub source: String,
+    pub count: usize,
+    pub types: Vec<PokemonType>,
+    pub max_height: f32,
+    pub max_weight: f32,
+    pub warnings: Vec<String>,
+}
+
+/// Describes the dataset the server searches, see `Dataset`.
+#[server]
+pub async fn get_pokedex_info() -> Result<PokedexInfo, ServerFnError> {
+    let dataset = server_dataset();
+    Ok(PokedexInfo {
+        source: dataset.source.clone(),
+        count: dataset.pokemon.len(),
+        types: dataset.types(),
+        max_height: dataset.max_height(),
+        max_weight: dataset.max_weight(),
+        warnings: dataset.warnings.clone(),
+    })
+}
+
+/// Runs the filter on the server and returns only the matches, in sort order.
+#[server]
+pub async fn search_pokemon(criteria: FilterCriteria) -> Result<Vec<Pokemon>, ServerFnError> {
+    let pokedex = &server_dataset().pokemon;
+    // `#[server]` drops `mut` from arguments
+    let mut criteria = criteria;
+    criteria.resolve_excluded_pokemon(pokedex);
+    Ok(criteria.apply(pokedex))
+}
+
+/// Looks up one Pokémon by `Pokemon::id`, e.g. "charizard-mega-x".
+#[server]
+pub async fn get_pokemon(id: String) -> Result<Option<Pokemon>, ServerFnError> {
+    Ok(server_dataset().pokemon.iter().find(|p| p.id == id).cloned())
+}
+
+// Loaded once per server process, from the file in POKEDEX_PATH if set and the
+// built-in dataset otherwise
+#[cfg(feature = "server")]
+fn server_dataset() -> &'static crate::dataset::Dataset {
+    use std::sync::OnceLock;
+    use crate::dataset::Dataset;
+
+    static DATASET: OnceLock<Dataset> = OnceLock::new();
+    DATASET.get_or_init(|| {
+        let path = std::env::var_os("POKEDEX_PATH").map(std::path::PathBuf::from);
+        let dataset = Dataset::load_startup(path.as_deref());
+        // Goes to the server log set up by `dioxus::launch`, clients get them
+        // through `get_pokedex_info`
+        for warning in &dataset.warnings {
+            dioxus::logger::tracing::warn!("{}", warning);
+        }
+        dataset
+    })
+}
@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use crate::pokemon::Pokemon;
+
+/// Columns of the results table that can be sorted on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortColumn {
+    Name,
+    Hp,
+    Attack,
+    Defense,
+    SpAtk,
+    SpDef,
+    Speed,
+    Total,
+    Height,
+    Weight,
+    Generation,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// One level of a multi-key sort; earlier keys take priority over later ones.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SortKey {
+    pub column: SortColumn,
+    pub direction: SortDirection,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 11] = [
+        SortColumn::Name, SortColumn::Hp, SortColumn::Attack, SortColumn::Defense,
+        SortColumn::SpAtk, SortColumn::SpDef, SortColumn::Speed, SortColumn::Total,
+        SortColumn::Height, SortColumn::Weight, SortColumn::Generation,
+    ];
+
+    /// Short identifier used on the command line and in saved state.
+    pub fn name(self) -> &'static str {
+        match self {
+            SortColumn::Name => "name",
+            SortColumn::Hp => "hp",
+            SortColumn::Attack => "attack",
+            SortColumn::Defense => "defense",
+            SortColumn::SpAtk => "sp_atk",
+            SortColumn::SpDef => "sp_def",
+            SortColumn::Speed => "speed",
+            SortColumn::Total => "total",
+            SortColumn::Height => "height",
+            SortColumn::Weight => "weight",
+            SortColumn::Generation => "gen",
+        }
+    }
+
+    // Names read best A-Z and generations oldest first, stats are wanted biggest first
+    pub fn default_direction(self) -> SortDirection {
+        match self {
+            SortColumn::Name | SortColumn::Generation => SortDirection::Ascending,
+            _ => SortDirection::Descending,
+        }
+    }
+
+    pub fn compare(self, a: &Pokemon, b: &Pokemon) -> Ordering {
+        match self {
+            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortColumn::Hp => a.hp.cmp(&b.hp),
+            SortColumn::Attack => a.attack.cmp(&b.attack),
+            SortColumn::Defense => a.defense.cmp(&b.defense),
+            SortColumn::SpAtk => a.sp_atk.cmp(&b.sp_atk),
+            SortColumn::SpDef => a.sp_def.cmp(&b.sp_def),
+            SortColumn::Speed => a.speed.cmp(&b.speed),
+            SortColumn::Total => a.total.cmp(&b.total),
+            SortColumn::Height => a.height.total_cmp(&b.height),
+            SortColumn::Weight => a.weight.total_cmp(&b.weight),
+            SortColumn::Generation => a.generation.cmp(&b.generation),
+        }
+    }
+}
+
+impl fmt::Display for SortColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SortColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "atk" => return Ok(SortColumn::Attack),
+            "def" => return Ok(SortColumn::Defense),
+            "spa" | "spatk" => return Ok(SortColumn::SpAtk),
+            "spd" | "spdef" => return Ok(SortColumn::SpDef),
+            "spe" => return Ok(SortColumn::Speed),
+            "bst" => return Ok(SortColumn::Total),
+            "generation" => return Ok(SortColumn::Generation),
+            _ => {}
+        }
+        SortColumn::ALL.iter()
+            .copied()
+            .find(|c| c.name() == s)
+            .ok_or_else(|| format!("unknown sort column '{}'", s))
+    }
+}
+
+impl SortDirection {
+    pub fn reversed(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    pub fn arrow(self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        }
+    }
+}
+
+impl SortKey {
+    pub fn new(column: SortColumn) -> Self {
+        Self { column, direction: column.default_direction() }
+    }
+
+    pub fn compare(&self, a: &Pokemon, b: &Pokemon) -> Ordering {
+        let ordering = self.column.compare(a, b);
+        match self.direction {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// Stable sort, so rows that tie on every key keep their dataset order.
+pub fn sort_pokemon(pokemon: &mut [Pokemon], keys: &[SortKey]) {
+    if keys.is_empty() {
+        return;
+    }
+    pokemon.sort_by(|a, b| {
+        keys.iter()
+            .map(|key| key.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Header click behaviour: a plain click sorts by that column alone, toggling
+/// the direction if it already was the only key; an additive (shift) click adds
+/// the column as a further key, or toggles it if it is already present.
+pub fn toggle_sort(keys: &mut Vec<SortKey>, column: SortColumn, additive: bool) {
+    let existing = keys.iter().position(|k| k.column == column);
+    if additive {
+        match existing {
+            Some(i) => keys[i].direction = keys[i].direction.reversed(),
+            None => keys.push(SortKey::new(column)),
+        }
+    } else {
+        let direction = match existing {
+            Some(i) if keys.len() == 1 => keys[i].direction.reversed(),
+            _ => column.default_direction(),
+        };
+        keys.clear();
+        keys.push(SortKey { column, direction });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{ids, pokedex};
+
+    fn sorted(keys: &[SortKey]) -> Vec<Pokemon> {
+        let mut pokemon = pokedex();
+        sort_pokemon(&mut pokemon, keys);
+        pokemon
+    }
+
+    #[test]
+    fn no_keys_keeps_dataset_order() {
+        assert_eq!(ids(&sorted(&[])), ids(&pokedex()));
+    }
+
+    #[test]
+    fn names_sort_case_insensitively() {
+        let mut pokemon = pokedex();
+        pokemon[0].name = "bulbasaur".to_string();
+        sort_pokemon(&mut pokemon, &[SortKey::new(SortColumn::Name)]);
+        assert_eq!(ids(&pokemon)[..5], ["bulbasaur", "charizard", "charizard-mega-x", "charmander", "flabébé"]);
+    }
+
+    #[test]
+    fn later_keys_break_ties_and_full_ties_stay_stable() {
+        let pokemon = sorted(&[SortKey::new(SortColumn::Speed), SortKey::new(SortColumn::Attack)]);
+        // The two Meowstic tie on both keys and keep their order
+        assert_eq!(ids(&pokemon)[..5], ["tauros", "raichu", "raichu-alolan", "meowstic-male", "meowstic-female"]);
+    }
+
+    #[test]
+    fn ascending_reverses_a_stat_sort() {
+        let pokemon = sorted(&[SortKey { column: SortColumn::Speed, direction: SortDirection::Ascending }]);
+        assert_eq!(ids(&pokemon)[..4], ["nidoran-female", "flabébé", "bulbasaur", "nidoran-male"]);
+    }
+
+    #[test]
+    fn plain_click_replaces_the_sort_and_toggles_a_lone_key() {
+        let mut keys = vec![SortKey::new(SortColumn::Name), SortKey::new(SortColumn::Speed)];
+        toggle_sort(&mut keys, SortColumn::Speed, false);
+        assert_eq!(keys, [SortKey::new(SortColumn::Speed)]);
+
+        toggle_sort(&mut keys, SortColumn::Speed, false);
+        assert_eq!(keys, [SortKey { column: SortColumn::Speed, direction: SortDirection::Ascending }]);
+    }
+
+    #[test]
+    fn shift_click_adds_a_key_or_toggles_it_in_place() {
+        let mut keys = vec![SortKey::new(SortColumn::Speed)];
+        toggle_sort(&mut keys, SortColumn::Name, true);
+        assert_eq!(keys, [SortKey::new(SortColumn::Speed), SortKey::new(SortColumn::Name)]);
+
+        toggle_sort(&mut keys, SortColumn::Speed, true);
+        assert_eq!(keys[0], SortKey { column: SortColumn::Speed, direction: SortDirection::Ascending });
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn columns_parse_from_names_and_short_aliases() {
+        assert_eq!("Speed".parse::<SortColumn>(), Ok(SortColumn::Speed));
+        assert_eq!("spe".parse::<SortColumn>(), Ok(SortColumn::Speed));
+        assert_eq!("bst".parse::<SortColumn>(), Ok(SortColumn::Total));
+        assert_eq!("gen".parse::<SortColumn>(), Ok(SortColumn::Generation));
+        assert_eq!("luck".parse::<SortColumn>(), Err("unknown sort column 'luck'".to_string()));
+        for column in SortColumn::ALL {
+            assert_eq!(column.name().parse::<SortColumn>(), Ok(column));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Fixtures shared by the unit tests: real rows of CompletePokemon.csv, read the
+// same way the converter and the app read a dataset.
+
+use std::path::PathBuf;
+use crate::csv_import;
+use crate::pokemon::Pokemon;
+
+/// Header row of CompletePokemon.csv, with the newline.
+pub const HEADER: &str = "Number,Generation,Name,Form,Type1,Type2,Total,HP,Attack,Defense,Sp. Atk,Sp. Def,Speed,Height,Weight\n";
+
+// In file order. Picked for dual types, type chart corner cases (4x, ¼, immune),
+// punctuation and accents in names, and Mega, regional, gender and breed forms.
+const ROWS: &str = "\
+1,1,Bulbasaur, ,Grass,Poison,318,45,49,49,65,65,45,0.7,6.9
+4,1,Charmander, ,Fire, ,309,39,52,43,60,50,65,0.6,8.5
+6,1,Charizard, ,Fire,Flying,534,78,84,78,109,85,100,1.7,90.5
+26,1,Raichu, ,Electric, ,485,60,90,55,90,80,110,0.8,30
+29,1,Nidoran,Female,Poison, ,275,55,47,52,40,40,41,0.4,7
+32,1,Nidoran,Male,Poison, ,273,46,57,40,40,40,50,0.5,9
+37,1,Vulpix, ,Fire, ,299,38,41,40,50,65,65,0.6,9.9
+122,1,Mr. Mime, ,Psychic,Fairy,460,40,45,65,100,120,90,1.3,54.5
+128,1,Tauros, ,Normal, ,490,75,100,95,40,70,110,1.4,88.4
+445,4,Garchomp, ,Dragon,Ground,600,108,130,95,80,85,102,1.9,95
+479,4,Rotom, ,Electric,Ghost,440,50,50,77,95,77,91,0.3,0.3
+479,4,Rotom,Wash Rotom,Electric,Water,520,50,65,107,105,107,86,0.3,0.3
+485,4,Heatran, ,Fire,Steel,600,91,90,106,130,106,77,1.7,430
+6,6,Charizard,Mega Charizard X,Fire,Dragon,634,78,130,111,130,85,100,1.7,110.5
+669,6,Flabébé, ,Fairy, ,303,44,38,39,61,79,42,0.1,0.1
+678,6,Meowstic,Male,Psychic, ,466,74,48,76,83,81,104,0.6,8.5
+678,6,Meowstic,Female,Psychic, ,466,74,48,76,83,81,104,0.6,8.5
+26,7,Raichu,Alolan Raichu,Electric,Psychic,485,60,85,50,95,85,110,0.7,21
+37,7,Vulpix,Alolan Vulpix,Ice, ,299,38,41,40,50,65,65,0.6,9.9
+128,9,Tauros,Combat Breed,Fighting, ,490,75,110,105,30,70,100,1.4,115
+";
+
+/// `HEADER` and the fixture rows as one CSV file.
+pub fn csv() -> String {
+    format!("{}{}", HEADER, ROWS)
+}
+
+/// The fixture rows parsed with `csv_import::parse_csv`.
+pub fn pokedex() -> Vec<Pokemon> {
+    csv_import::parse_csv(csv().as_bytes()).expect("fixture CSV parses").pokemon
+}
+
+/// One fixture entry by `Pokemon::id`.
+pub fn pokemon(id: &str) -> Pokemon {
+    pokedex().into_iter()
+        .find(|p| p.id == id)
+        .unwrap_or_else(|| panic!("no fixture Pokémon with ID '{}'", id))
+}
+
+pub fn ids(pokemon: &[Pokemon]) -> Vec<&str> {
+    pokemon.iter().map(|p| p.id.as_str()).collect()
+}
+
+/// An empty directory of its own under the system temp dir, for tests that write
+/// files. Callers remove it when done.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pokemon_filter-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("temp dir can be created");
+    dir
+}
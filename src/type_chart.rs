@@ -0,0 +1,131 @@
+use crate::pokemon::{Pokemon, PokemonType};
+use PokemonType::*;
+
+/// Damage multiplier of a single attacking type against a single defending type
+/// (current-generation chart).
+pub fn effectiveness(attacking: PokemonType, defending: PokemonType) -> f32 {
+    match (attacking, defending) {
+        // Immunities
+        (Normal, Ghost) | (Electric, Ground) | (Fighting, Ghost) | (Poison, Steel)
+        | (Ground, Flying) | (Psychic, Dark) | (Ghost, Normal) | (Dragon, Fairy) => 0.0,
+
+        // Super effective
+        (Fire, Grass | Ice | Bug | Steel)
+        | (Water, Fire | Ground | Rock)
+        | (Electric, Water | Flying)
+        | (Grass, Water | Ground | Rock)
+        | (Ice, Grass | Ground | Flying | Dragon)
+        | (Fighting, Normal | Ice | Rock | Dark | Steel)
+        | (Poison, Grass | Fairy)
+        | (Ground, Fire | Electric | Poison | Rock | Steel)
+        | (Flying, Grass | Fighting | Bug)
+        | (Psychic, Fighting | Poison)
+        | (Bug, Grass | Psychic | Dark)
+        | (Rock, Fire | Ice | Flying | Bug)
+        | (Ghost, Psychic | Ghost)
+        | (Dragon, Dragon)
+        | (Dark, Psychic | Ghost)
+        | (Steel, Ice | Rock | Fairy)
+        | (Fairy, Fighting | Dragon | Dark) => 2.0,
+
+        // Not very effective
+        (Normal, Rock | Steel)
+        | (Fire, Fire | Water | Rock | Dragon)
+        | (Water, Water | Grass | Dragon)
+        | (Electric, Electric | Grass | Dragon)
+        | (Grass, Fire | Grass | Poison | Flying | Bug | Dragon | Steel)
+        | (Ice, Fire | Water | Ice | Steel)
+        | (Fighting, Poison | Flying | Psychic | Bug | Fairy)
+        | (Poison, Poison | Ground | Rock | Ghost)
+        | (Ground, Grass | Bug)
+        | (Flying, Electric | Rock | Steel)
+        | (Psychic, Psychic | Steel)
+        | (Bug, Fire | Fighting | Poison | Flying | Ghost | Steel | Fairy)
+        | (Rock, Fighting | Ground | Steel)
+        | (Ghost, Dark)
+        | (Dragon, Steel)
+        | (Dark, Fighting | Dark | Fairy)
+        | (Steel, Fire | Water | Electric | Steel)
+        | (Fairy, Fire | Poison | Steel) => 0.5,
+
+        _ => 1.0,
+    }
+}
+
+/// Multiplier an attack of the given type deals to this Pokémon: one of
+/// 0, ¼, ½, 1, 2 or 4 depending on how its two types combine.
+pub fn defensive_multiplier(attacking: PokemonType, pokemon: &Pokemon) -> f32 {
+    let first = effectiveness(attacking, pokemon.type1);
+    match pokemon.type2 {
+        Some(type2) => first * effectiveness(attacking, type2),
+        None => first,
+    }
+}
+
+/// The multiplier of every attacking type against this Pokémon, in type order.
+pub fn defensive_profile(pokemon: &Pokemon) -> Vec<(PokemonType, f32)> {
+    PokemonType::ALL.iter()
+        .map(|&t| (t, defensive_multiplier(t, pokemon)))
+        .collect()
+}
+
+pub fn is_weak_to(attacking: PokemonType, pokemon: &Pokemon) -> bool {
+    defensive_multiplier(attacking, pokemon) > 1.0
+}
+
+/// Takes reduced but non-zero damage; immunities are reported by `is_immune_to`.
+pub fn resists(attacking: PokemonType, pokemon: &Pokemon) -> bool {
+    let multiplier = defensive_multiplier(attacking, pokemon);
+    multiplier > 0.0 && multiplier < 1.0
+}
+
+pub fn is_immune_to(attacking: PokemonType, pokemon: &Pokemon) -> bool {
+    defensive_multiplier(attacking, pokemon) == 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::pokemon;
+
+    #[test]
+    fn dual_types_multiply() {
+        assert_eq!(defensive_multiplier(Ice, &pokemon("garchomp")), 4.0);
+        assert_eq!(defensive_multiplier(Grass, &pokemon("heatran")), 0.25);
+        // Steel's weakness cancels Fire's resistance
+        assert_eq!(defensive_multiplier(Fire, &pokemon("heatran")), 1.0);
+        assert_eq!(defensive_multiplier(Fire, &pokemon("charmander")), 0.5);
+    }
+
+    #[test]
+    fn an_immunity_zeroes_the_other_types_weakness() {
+        let charizard = pokemon("charizard");
+        assert_eq!(effectiveness(Ground, Fire), 2.0);
+        assert_eq!(defensive_multiplier(Ground, &charizard), 0.0);
+        assert!(is_immune_to(Ground, &charizard));
+        assert!(!is_weak_to(Ground, &charizard));
+
+        // Levitate is an ability, which the chart doesn't model: by type alone
+        // Wash Rotom is weak to Ground
+        assert_eq!(defensive_multiplier(Ground, &pokemon("rotom-wash")), 2.0);
+    }
+
+    #[test]
+    fn resists_leaves_out_immunities() {
+        let garchomp = pokemon("garchomp");
+        assert!(resists(Fire, &garchomp));
+        assert!(!resists(Electric, &garchomp));
+        assert!(is_immune_to(Electric, &garchomp));
+        assert!(!resists(Normal, &garchomp));
+    }
+
+    #[test]
+    fn the_profile_lists_every_attacking_type_in_order() {
+        let profile = defensive_profile(&pokemon("mr-mime"));
+        assert_eq!(profile.len(), PokemonType::ALL.len());
+        assert_eq!(profile[0], (Normal, 1.0));
+        assert!(profile.contains(&(Dragon, 0.0)));
+        assert!(profile.contains(&(Steel, 2.0)));
+        assert!(profile.contains(&(Fighting, 0.25)));
+    }
+}
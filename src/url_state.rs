@@ -0,0 +1,339 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::filter::{FilterCriteria, MAX_TOTAL};
+use crate::pokemon::{FormKind, ParseTypeError, PokemonType};
+use crate::sort::{SortDirection, SortKey};
+
+// Query key, then the current and default (min, max) of an integer range
+type IntRange = (&'static str, (u16, u16), (u16, u16));
+
+/// Encodes the criteria as a URL query string such as
+/// `name=char&has=fire,flying&gen=1-3&spe=100-255&sort=-speed`.
+///
+/// Anything left at its default is omitted so shared links stay short.
+pub fn to_query_string(c: &FilterCriteria) -> String {
+    let defaults = FilterCriteria::default();
+    let mut query = form_urlencoded::Serializer::new(String::new());
+
+    if !c.name.is_empty() {
+        query.append_pair("name", &c.name);
+    }
+    if let Some(t) = c.type1 {
+        query.append_pair("type1", t.name());
+    }
+    if let Some(t) = c.type2 {
+        query.append_pair("type2", t.name());
+    }
+    for (key, types) in [
+        ("has", &c.has_types),
+        ("exclude", &c.excluded_types),
+        ("weak", &c.weak_to),
+        ("resists", &c.resists),
+        ("immune", &c.immune_to),
+    ] {
+        if !types.is_empty() {
+            query.append_pair(key, &type_list(types));
+        }
+    }
+
+    for (key, kinds) in [("form", &c.form_kinds), ("exclude_form", &c.excluded_form_kinds)] {
+        if !kinds.is_empty() {
+            let names: Vec<&str> = kinds.iter().map(|k| k.name()).collect();
+            query.append_pair(key, &names.join(","));
+        }
+    }
+
+    let int_ranges: [IntRange; 8] = [
+        ("gen", (c.min_gen.into(), c.max_gen.into()), (defaults.min_gen.into(), defaults.max_gen.into())),
+        ("hp", (c.min_hp.into(), c.max_hp.into()), (0, u8::MAX.into())),
+        ("atk", (c.min_attack.into(), c.max_attack.into()), (0, u8::MAX.into())),
+        ("def", (c.min_defense.into(), c.max_defense.into()), (0, u8::MAX.into())),
+        ("spa", (c.min_sp_atk.into(), c.max_sp_atk.into()), (0, u8::MAX.into())),
+        ("spd", (c.min_sp_def.into(), c.max_sp_def.into()), (0, u8::MAX.into())),
+        ("spe", (c.min_speed.into(), c.max_speed.into()), (0, u8::MAX.into())),
+        ("bst", (c.min_total, c.max_total), (0, MAX_TOTAL)),
+    ];
+    for (key, (min, max), default) in int_ranges {
+        if (min, max) != default {
+            query.append_pair(key, &format!("{}-{}", min, max));
+        }
+    }
+
+    for (key, min, max) in [
+        ("height", c.min_height, c.max_height),
+        ("weight", c.min_weight, c.max_weight),
+    ] {
+        if min > 0.0 || max < f32::MAX {
+            // An open upper end is left blank, e.g. "weight=100-"
+            let max = if max < f32::MAX { max.to_string() } else { String::new() };
+            query.append_pair(key, &format!("{}-{}", min, max));
+        }
+    }
+
+    // One pair per hidden Pokémon ID
+    for name in &c.excluded_pokemon {
+        query.append_pair("hide", name);
+    }
+
+    if !c.sort.is_empty() {
+        let keys: Vec<String> = c.sort.iter()
+            .map(|key| match key.direction {
+                SortDirection::Ascending => key.column.name().to_string(),
+                SortDirection::Descending => format!("-{}", key.column.name()),
+            })
+            .collect();
+        query.append_pair("sort", &keys.join(","));
+    }
+
+    query.finish()
+}
+
+/// Reads criteria back from a query string, with or without the leading `?`.
+///
+/// Unknown keys and values that don't parse are skipped rather than rejected,
+/// so a hand-edited or outdated link still opens with whatever it got right.
+pub fn from_query_string(query: &str) -> FilterCriteria {
+    let mut c = FilterCriteria::default();
+    let query = query.strip_prefix('?').unwrap_or(query);
+
+    for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+        let _ = apply_pair(&mut c, &key, &value);
+    }
+    c
+}
+
+/// Like `from_query_string` but for callers that should report bad input, such
+/// as the JSON API: every pair must be a known key with a value that parses.
+/// Takes already decoded pairs so the caller can handle its own extra keys.
+pub fn parse_query_pairs<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> Result<FilterCriteria, QueryStringError>
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut c = FilterCriteria::default();
+    for (key, value) in pairs {
+        apply_pair(&mut c, key.as_ref(), value.as_ref()).map_err(|message| QueryStringError {
+            key: key.as_ref().to_string(),
+            message,
+        })?;
+    }
+    Ok(c)
+}
+
+/// A query string pair `parse_query_pairs` rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryStringError {
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for QueryStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+impl std::error::Error for QueryStringError {}
+
+fn apply_pair(c: &mut FilterCriteria, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "name" => c.name = value.to_string(),
+        "type1" => c.type1 = Some(value.parse().map_err(|e: ParseTypeError| e.to_string())?),
+        "type2" => c.type2 = Some(value.parse().map_err(|e: ParseTypeError| e.to_string())?),
+        "has" => c.has_types = parse_types(value)?,
+        "exclude" => c.excluded_types = parse_types(value)?,
+        "weak" => c.weak_to = parse_types(value)?,
+        "resists" => c.resists = parse_types(value)?,
+        "immune" => c.immune_to = parse_types(value)?,
+        "form" => c.form_kinds = FormKind::parse_list(value).map_err(|e| e.to_string())?,
+        "exclude_form" => c.excluded_form_kinds = FormKind::parse_list(value).map_err(|e| e.to_string())?,
+        "gen" => set_range(value, &mut c.min_gen, &mut c.max_gen)?,
+        "hp" => set_range(value, &mut c.min_hp, &mut c.max_hp)?,
+        "atk" => set_range(value, &mut c.min_attack, &mut c.max_attack)?,
+        "def" => set_range(value, &mut c.min_defense, &mut c.max_defense)?,
+        "spa" => set_range(value, &mut c.min_sp_atk, &mut c.max_sp_atk)?,
+        "spd" => set_range(value, &mut c.min_sp_def, &mut c.max_sp_def)?,
+        "spe" => set_range(value, &mut c.min_speed, &mut c.max_speed)?,
+        "bst" => set_range(value, &mut c.min_total, &mut c.max_total)?,
+        "height" => set_float_range(value, &mut c.min_height, &mut c.max_height)?,
+        "weight" => set_float_range(value, &mut c.min_weight, &mut c.max_weight)?,
+        "hide" => c.excluded_pokemon.push(value.to_string()),
+        "sort" => c.sort = parse_sort(value)?,
+        _ => return Err("unknown parameter".to_string()),
+    }
+    Ok(())
+}
+
+fn type_list(types: &[PokemonType]) -> String {
+    types.iter().map(|t| t.name()).collect::<Vec<_>>().join(",")
+}
+
+fn parse_types(value: &str) -> Result<Vec<PokemonType>, String> {
+    value.split(',')
+        .map(|t| t.parse().map_err(|e: ParseTypeError| e.to_string()))
+        .collect()
+}
+
+fn parse_sort(value: &str) -> Result<Vec<SortKey>, String> {
+    value.split(',')
+        .map(|key| {
+            let (name, direction) = match key.strip_prefix('-') {
+                Some(name) => (name, SortDirection::Descending),
+                None => (key, SortDirection::Ascending),
+            };
+            let column = name.parse()?;
+            Ok(SortKey { column, direction })
+        })
+        .collect()
+}
+
+// "min-max" where either end may be blank to keep the default, e.g. "100-",
+// or a single value for an exact match
+fn set_range<T: FromStr + PartialOrd + Copy + fmt::Display>(value: &str, min: &mut T, max: &mut T) -> Result<(), String> {
+    let (lo, hi) = value.split_once('-').unwrap_or((value, value));
+    let bound = |s: &str, default: T| -> Result<T, String> {
+        if s.is_empty() {
+            return Ok(default);
+        }
+        s.trim().parse().map_err(|_| format!("'{}' is not a number or is out of range", s))
+    };
+    let (lo, hi) = (bound(lo, *min)?, bound(hi, *max)?);
+    if lo > hi {
+        return Err(format!("range {}-{} is backwards", lo, hi));
+    }
+    *min = lo;
+    *max = hi;
+    Ok(())
+}
+
+// As `set_range`, also rejecting the NaN and infinite values `f32` parses
+fn set_float_range(value: &str, min: &mut f32, max: &mut f32) -> Result<(), String> {
+    if value.split('-').any(|part| part.parse::<f32>().is_ok_and(|n| !n.is_finite())) {
+        return Err(format!("'{}' is not a valid range", value));
+    }
+    set_range(value, min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pokemon::Pokemon;
+    use crate::sort::SortColumn;
+    use PokemonType::*;
+
+    fn parse(query: &str) -> Result<FilterCriteria, QueryStringError> {
+        parse_query_pairs(form_urlencoded::parse(query.as_bytes()))
+    }
+
+    fn error(query: &str) -> String {
+        parse(query).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn defaults_encode_to_an_empty_string() {
+        assert_eq!(to_query_string(&FilterCriteria::default()), "");
+        assert_eq!(from_query_string(""), FilterCriteria::default());
+        assert_eq!(from_query_string("?"), FilterCriteria::default());
+    }
+
+    #[test]
+    fn every_field_round_trips() {
+        let c = FilterCriteria {
+            name: "Mr. Mime & co".to_string(),
+            type1: Some(Psychic),
+            type2: Some(Fairy),
+            has_types: vec![Fire, Flying],
+            excluded_types: vec![Ground],
+            weak_to: vec![Rock],
+            resists: vec![Grass, Bug],
+            immune_to: vec![Ground],
+            form_kinds: vec![FormKind::Alolan, FormKind::Galarian],
+            excluded_form_kinds: vec![FormKind::Mega],
+            min_gen: 2,
+            max_gen: 4,
+            min_hp: 10,
+            max_attack: 200,
+            min_defense: 30,
+            max_defense: 30,
+            min_sp_atk: 1,
+            max_sp_def: 254,
+            min_speed: 100,
+            min_total: 500,
+            max_total: 600,
+            min_height: 0.5,
+            max_height: 2.25,
+            min_weight: 100.0,
+            excluded_pokemon: vec!["charizard".to_string(), "charizard-mega-charizard-x".to_string()],
+            sort: vec![
+                SortKey { column: SortColumn::Speed, direction: SortDirection::Descending },
+                SortKey { column: SortColumn::Name, direction: SortDirection::Ascending },
+            ],
+            ..FilterCriteria::default()
+        };
+        let query = to_query_string(&c);
+        assert_eq!(from_query_string(&query), c);
+        assert_eq!(from_query_string(&format!("?{}", query)), c);
+        assert_eq!(parse(&query).unwrap(), c);
+    }
+
+    #[test]
+    fn only_changed_fields_are_written() {
+        let c = FilterCriteria {
+            has_types: vec![Fire, Flying],
+            min_gen: 1,
+            max_gen: 3,
+            min_speed: 100,
+            min_weight: 100.0,
+            excluded_pokemon: vec!["pikachu".to_string(), "raichu".to_string()],
+            sort: vec![SortKey { column: SortColumn::Speed, direction: SortDirection::Descending }],
+            ..FilterCriteria::default()
+        };
+        assert_eq!(
+            to_query_string(&c),
+            "has=Fire%2CFlying&gen=1-3&spe=100-255&weight=100-&hide=pikachu&hide=raichu&sort=-speed"
+        );
+    }
+
+    #[test]
+    fn range_ends_may_be_blank_or_a_single_value() {
+        let c = from_query_string("spe=100-&hp=-80&bst=500&height=1.5-");
+        assert_eq!((c.min_speed, c.max_speed), (100, u8::MAX));
+        assert_eq!((c.min_hp, c.max_hp), (0, 80));
+        assert_eq!((c.min_total, c.max_total), (500, 500));
+        assert_eq!((c.min_height, c.max_height), (1.5, f32::MAX));
+    }
+
+    #[test]
+    fn values_are_parsed_case_insensitively() {
+        let c = from_query_string("type1=fire&has=FLYING&form=regional&sort=Speed,-BST");
+        assert_eq!(c.type1, Some(Fire));
+        assert_eq!(c.has_types, [Flying]);
+        assert_eq!(c.form_kinds, FormKind::REGIONAL);
+        assert_eq!(c.sort, [
+            SortKey { column: SortColumn::Speed, direction: SortDirection::Ascending },
+            SortKey { column: SortColumn::Total, direction: SortDirection::Descending },
+        ]);
+    }
+
+    #[test]
+    fn the_lenient_parser_skips_bad_pairs() {
+        let c = from_query_string("has=Fire,Fyre&gen=banana&spe=100-&foo=1&hp=90-10");
+        assert!(c.has_types.is_empty());
+        assert_eq!((c.min_gen, c.max_gen), (1, Pokemon::MAX_GENERATION));
+        assert_eq!((c.min_hp, c.max_hp), (0, u8::MAX));
+        assert_eq!(c.min_speed, 100);
+    }
+
+    #[test]
+    fn the_strict_parser_names_the_bad_pair() {
+        assert_eq!(error("has=Fire,Fyre"), "has: unknown Pokémon type 'Fyre'");
+        assert_eq!(error("gen=banana"), "gen: 'banana' is not a number or is out of range");
+        assert_eq!(error("hp=300-400"), "hp: '300' is not a number or is out of range");
+        assert_eq!(error("bst=600-500"), "bst: range 600-500 is backwards");
+        assert_eq!(error("height=inf-"), "height: 'inf-' is not a valid range");
+        assert_eq!(error("weight=NaN"), "weight: 'NaN' is not a valid range");
+        assert_eq!(error("sort=-shininess"), "sort: unknown sort column 'shininess'");
+        assert!(error("form=shiny").starts_with("form: unknown form kind 'shiny'"));
+        assert_eq!(error("spe=100-&foo=1"), "foo: unknown parameter");
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::csv_import::{self, Columns, CsvError};
+use crate::pokemon::{Pokemon, PokemonType};
+
+/// A suspicious value in a source CSV, with its 1-based line number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub line: u64,
+    pub kind: IssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    TotalMismatch { total: u16, sum: u16 },
+    ZeroStat(&'static str),
+    ZeroHeight,
+    ZeroWeight,
+    // Same name and form as an earlier row
+    Duplicate { name: String, first_line: u64 },
+    // Different name or form, but the same `Pokemon::id` as an earlier row
+    DuplicateId { id: String, first_line: u64 },
+    UnknownType(String),
+    UnexpectedGeneration(u8),
+    // The row can't be turned into a Pokémon, so converting would drop it
+    Unparseable(String),
+    // The CSV reader couldn't read the row at all, e.g. a wrong field count
+    Unreadable(String),
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            IssueKind::TotalMismatch { total, sum } => {
+                write!(f, "total is {} but the six stats add up to {}", total, sum)
+            }
+            IssueKind::ZeroStat(stat) => write!(f, "{} is 0", stat),
+            IssueKind::ZeroHeight => write!(f, "height is 0"),
+            IssueKind::ZeroWeight => write!(f, "weight is 0"),
+            IssueKind::Duplicate { name, first_line } => {
+                write!(f, "{} already appears on line {}", name, first_line)
+            }
+            IssueKind::DuplicateId { id, first_line } => {
+                write!(f, "ID '{}' is already used on line {}", id, first_line)
+            }
+            IssueKind::UnknownType(name) => write!(f, "unknown type '{}'", name),
+            IssueKind::UnexpectedGeneration(generation) => write!(
+                f,
+                "generation {} is outside 1-{}",
+                generation,
+                Pokemon::MAX_GENERATION
+            ),
+            IssueKind::Unparseable(message) => write!(f, "row can't be parsed: {}", message),
+            IssueKind::Unreadable(message) => write!(f, "row can't be read: {}", message),
+        }
+    }
+}
+
+/// Checks every row of a CSV in the CompletePokemon.csv layout for values that
+/// parse but are probably wrong, and for rows `csv_import::parse_csv` would
+/// have to skip.
+pub fn validate_csv(bytes: &[u8]) -> Result<Vec<Issue>, CsvError> {
+    let mut rdr = csv::Reader::from_reader(bytes);
+    let headers = rdr.headers().map_err(CsvError::Csv)?.clone();
+    let columns = Columns::from_headers(&headers);
+
+    let mut issues = Vec::new();
+    // Display name or ID -> line it was first seen on
+    let mut seen: HashMap<String, u64> = HashMap::new();
+    let mut seen_ids: HashMap<String, u64> = HashMap::new();
+
+    for result in rdr.records() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                issues.push(Issue { line, kind: IssueKind::Unreadable(e.to_string()) });
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line());
+
+        // Types are checked on the raw text so every bad one gets reported,
+        // not just the first one parsing stops at
+        let mut known_types = true;
+        for column in [columns.type1, columns.type2] {
+            let name = record.get(column).unwrap_or("").trim();
+            if (column == columns.type1 || !name.is_empty()) && name.parse::<PokemonType>().is_err() {
+                issues.push(Issue { line, kind: IssueKind::UnknownType(name.to_string()) });
+                known_types = false;
+            }
+        }
+        if !known_types {
+            continue;
+        }
+
+        let p = match csv_import::parse_pokemon_from_record(&record, &columns) {
+            Ok(p) => p,
+            Err(e) => {
+                issues.push(Issue { line, kind: IssueKind::Unparseable(e.to_string()) });
+                continue;
+            }
+        };
+        issues.extend(check_pokemon(&p).into_iter().map(|kind| Issue { line, kind }));
+
+        let name = p.display_name();
+        if let Some(&first_line) = seen.get(&name) {
+            issues.push(Issue { line, kind: IssueKind::Duplicate { name: name.clone(), first_line } });
+        } else if let Some(&first_line) = seen_ids.get(&p.id) {
+            issues.push(Issue { line, kind: IssueKind::DuplicateId { id: p.id.clone(), first_line } });
+        }
+        seen.entry(name).or_insert(line);
+        seen_ids.entry(p.id).or_insert(line);
+    }
+
+    Ok(issues)
+}
+
+/// Problems visible from a single record.
+pub fn check_pokemon(p: &Pokemon) -> Vec<IssueKind> {
+    let mut issues = Vec::new();
+
+    let sum: u16 = p.base_stats().iter().map(|&(_, value)| u16::from(value)).sum();
+    if sum != p.total {
+        issues.push(IssueKind::TotalMismatch { total: p.total, sum });
+    }
+    for (stat, value) in p.base_stats() {
+        if value == 0 {
+            issues.push(IssueKind::ZeroStat(stat));
+        }
+    }
+    if p.height <= 0.0 {
+        issues.push(IssueKind::ZeroHeight);
+    }
+    if p.weight <= 0.0 {
+        issues.push(IssueKind::ZeroWeight);
+    }
+    if !(1..=Pokemon::MAX_GENERATION).contains(&p.generation) {
+        issues.push(IssueKind::UnexpectedGeneration(p.generation));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{self, HEADER};
+
+    fn validate(rows: &str) -> Vec<String> {
+        let csv = format!("{}{}", HEADER, rows);
+        validate_csv(csv.as_bytes()).unwrap().iter().map(Issue::to_string).collect()
+    }
+
+    #[test]
+    fn clean_rows_have_no_issues() {
+        assert_eq!(validate_csv(test_data::csv().as_bytes()).unwrap(), []);
+    }
+
+    #[test]
+    fn stat_problems_are_reported_with_their_line() {
+        let issues = validate(
+            "1,1,Bulbasaur, ,Grass,Poison,318,45,49,49,65,65,45,0.7,6.9\n\
+             2,1,Ivysaur, ,Grass,Poison,400,60,62,63,80,80,60,1,13\n\
+             3,10,Venusaur, ,Grass,Poison,425,0,82,83,100,100,60,0,0\n",
+        );
+        assert_eq!(issues, [
+            "line 3: total is 400 but the six stats add up to 405",
+            "line 4: HP is 0",
+            "line 4: height is 0",
+            "line 4: weight is 0",
+            "line 4: generation 10 is outside 1-9",
+        ]);
+    }
+
+    #[test]
+    fn every_unknown_type_in_a_row_is_reported() {
+        let issues = validate("1,1,Bulbasaur, ,Grsas,Poisen,318,45,49,49,65,65,45,0.7,6.9\n");
+        assert_eq!(issues, [
+            "line 2: unknown type 'Grsas'",
+            "line 2: unknown type 'Poisen'",
+        ]);
+        assert_eq!(validate("1,1,Bulbasaur, ,,,318,45,49,49,65,65,45,0.7,6.9\n"), ["line 2: unknown type ''"]);
+    }
+
+    #[test]
+    fn repeated_names_and_ids_point_at_the_first_row() {
+        let issues = validate(
+            "122,1,Mr. Mime, ,Psychic,Fairy,460,40,45,65,100,120,90,1.3,54.5\n\
+             122,1,Mr. Mime, ,Psychic,Fairy,460,40,45,65,100,120,90,1.3,54.5\n\
+             122,1,Mr Mime, ,Psychic,Fairy,460,40,45,65,100,120,90,1.3,54.5\n",
+        );
+        assert_eq!(issues, [
+            "line 3: Mr. Mime already appears on line 2",
+            "line 4: ID 'mr-mime' is already used on line 2",
+        ]);
+    }
+
+    #[test]
+    fn rows_the_importer_would_skip_are_reported() {
+        let issues = validate(
+            "1,1,Bulbasaur, ,Grass,Poison,318,45,49,49,65,65,abc,0.7,6.9\n\
+             2,1,Ivysaur\n\
+             3,1,Venusaur, ,Grass,Poison,525,80,82,83,100,100,80,2,100\n",
+        );
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert_eq!(issues[0], "line 2: row can't be parsed: Speed 'abc' is not a valid number");
+        assert!(issues[1].starts_with("line 3: row can't be read: "), "{}", issues[1]);
+    }
+}
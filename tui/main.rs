@@ -0,0 +1,433 @@
+use std::path::PathBuf;
+use anyhow::{bail, Context};
+use pokemon_filter::dataset;
+use pokemon_filter::filter::{FilterCriteria, MAX_TOTAL};
+use pokemon_filter::pokemon::{Pokemon, PokemonType};
+use pokemon_filter::sort::{self, SortColumn};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, List, ListItem, ListState, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+const USAGE: &str = "\
+Usage: pokefilter-tui [--data PATH]
+
+Browses the pokedex in the terminal with the same filters as the app.
+
+Options:
+      --data PATH      Pokedex .bin or CompletePokemon-style .csv to read
+                       (default: the built-in dataset)
+  -h, --help           Print this help
+
+Keys:
+  Tab / Shift+Tab      Move between the search box, type pickers, ranges and table
+  Left / Right         Change the selected type, lower bound or sort column
+  Shift+Left/Right     Change the upper bound of the selected range
+  Up / Down            Pick a range, or scroll the table (also PgUp, PgDn, Home, End)
+  s / S                Sort by the highlighted column, S adds it as a further key
+  Ctrl+R               Reset every filter
+  Esc / Ctrl+C         Quit";
+
+/// Which part of the screen takes key presses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    Search,
+    Type1,
+    Type2,
+    Ranges,
+    Table,
+}
+
+impl Focus {
+    const ORDER: [Focus; 5] = [Focus::Search, Focus::Type1, Focus::Type2, Focus::Ranges, Focus::Table];
+
+    fn step(self, forward: bool) -> Self {
+        let i = Self::ORDER.iter().position(|&f| f == self).unwrap_or(0);
+        let len = Self::ORDER.len();
+        Self::ORDER[if forward { (i + 1) % len } else { (i + len - 1) % len }]
+    }
+}
+
+/// The min-max filters adjustable from the ranges panel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RangeField {
+    Generation,
+    Hp,
+    Attack,
+    Defense,
+    SpAtk,
+    SpDef,
+    Speed,
+    Total,
+}
+
+impl RangeField {
+    const ALL: [RangeField; 8] = [
+        RangeField::Generation, RangeField::Hp, RangeField::Attack, RangeField::Defense,
+        RangeField::SpAtk, RangeField::SpDef, RangeField::Speed, RangeField::Total,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            RangeField::Generation => "Gen",
+            RangeField::Hp => "HP",
+            RangeField::Attack => "Atk",
+            RangeField::Defense => "Def",
+            RangeField::SpAtk => "Sp.Atk",
+            RangeField::SpDef => "Sp.Def",
+            RangeField::Speed => "Speed",
+            RangeField::Total => "Total",
+        }
+    }
+
+    // Lowest and highest value, and how far one key press moves a bound
+    fn limits(self) -> (u16, u16, u16) {
+        match self {
+            RangeField::Generation => (1, 9, 1),
+            RangeField::Total => (0, MAX_TOTAL, 10),
+            _ => (0, u8::MAX.into(), 5),
+        }
+    }
+
+    fn get(self, c: &FilterCriteria) -> (u16, u16) {
+        match self {
+            RangeField::Generation => (c.min_gen.into(), c.max_gen.into()),
+            RangeField::Hp => (c.min_hp.into(), c.max_hp.into()),
+            RangeField::Attack => (c.min_attack.into(), c.max_attack.into()),
+            RangeField::Defense => (c.min_defense.into(), c.max_defense.into()),
+            RangeField::SpAtk => (c.min_sp_atk.into(), c.max_sp_atk.into()),
+            RangeField::SpDef => (c.min_sp_def.into(), c.max_sp_def.into()),
+            RangeField::Speed => (c.min_speed.into(), c.max_speed.into()),
+            RangeField::Total => (c.min_total, c.max_total),
+        }
+    }
+
+    fn set(self, c: &mut FilterCriteria, (min, max): (u16, u16)) {
+        // Every field but the total is a u8, and `limits` keeps the values in range
+        let (min8, max8) = (min.min(255) as u8, max.min(255) as u8);
+        match self {
+            RangeField::Generation => (c.min_gen, c.max_gen) = (min8, max8),
+            RangeField::Hp => (c.min_hp, c.max_hp) = (min8, max8),
+            RangeField::Attack => (c.min_attack, c.max_attack) = (min8, max8),
+            RangeField::Defense => (c.min_defense, c.max_defense) = (min8, max8),
+            RangeField::SpAtk => (c.min_sp_atk, c.max_sp_atk) = (min8, max8),
+            RangeField::SpDef => (c.min_sp_def, c.max_sp_def) = (min8, max8),
+            RangeField::Speed => (c.min_speed, c.max_speed) = (min8, max8),
+            RangeField::Total => (c.min_total, c.max_total) = (min, max),
+        }
+    }
+
+    /// Moves one bound by whole steps, pushing the other along so min never passes max.
+    fn adjust(self, c: &mut FilterCriteria, upper: bool, steps: i32) {
+        let (lo, hi, step) = self.limits();
+        let (mut min, mut max) = self.get(c);
+        let moved = |value: u16| {
+            (i32::from(value) + steps * i32::from(step)).clamp(lo.into(), hi.into()) as u16
+        };
+        if upper {
+            max = moved(max);
+            min = min.min(max);
+        } else {
+            min = moved(min);
+            max = max.max(min);
+        }
+        self.set(c, (min, max));
+    }
+}
+
+struct App {
+    pokedex: Vec<Pokemon>,
+    criteria: FilterCriteria,
+    results: Vec<Pokemon>,
+    focus: Focus,
+    range_state: ListState,
+    table_state: TableState,
+    // Column the s/S keys sort by, an index into `SortColumn::ALL`
+    sort_cursor: usize,
+}
+
+impl App {
+    fn new(pokedex: Vec<Pokemon>) -> Self {
+        let mut app = Self {
+            pokedex,
+            criteria: FilterCriteria::default(),
+            results: Vec::new(),
+            focus: Focus::Search,
+            range_state: ListState::default().with_selected(Some(0)),
+            table_state: TableState::default(),
+            sort_cursor: 0,
+        };
+        app.refresh();
+        app
+    }
+
+    // Reruns the filter after any change to the criteria
+    fn refresh(&mut self) {
+        self.results = self.criteria.apply(&self.pokedex);
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(if self.results.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.results.len() - 1))
+        });
+    }
+
+    /// Handles one key press, returning false once the user wants to quit.
+    fn on_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if ctrl => return false,
+            KeyCode::Char('r') if ctrl => {
+                // Keep the sort, like the app's query box does
+                let sort = std::mem::take(&mut self.criteria.sort);
+                self.criteria = FilterCriteria { sort, ..FilterCriteria::default() };
+            }
+            KeyCode::Tab => self.focus = self.focus.step(true),
+            KeyCode::BackTab => self.focus = self.focus.step(false),
+            _ => match self.focus {
+                Focus::Search => match key.code {
+                    KeyCode::Char(c) => self.criteria.name.push(c),
+                    KeyCode::Backspace => {
+                        self.criteria.name.pop();
+                    }
+                    _ => return true,
+                },
+                Focus::Type1 | Focus::Type2 => {
+                    let forward = match key.code {
+                        KeyCode::Right => true,
+                        KeyCode::Left => false,
+                        _ => return true,
+                    };
+                    let slot = if self.focus == Focus::Type1 {
+                        &mut self.criteria.type1
+                    } else {
+                        &mut self.criteria.type2
+                    };
+                    *slot = cycle_type(*slot, forward);
+                }
+                Focus::Ranges => {
+                    let row = self.range_state.selected().unwrap_or(0);
+                    match key.code {
+                        KeyCode::Up => self.range_state.select(Some(row.saturating_sub(1))),
+                        KeyCode::Down => self.range_state.select(Some((row + 1).min(RangeField::ALL.len() - 1))),
+                        KeyCode::Left => RangeField::ALL[row].adjust(&mut self.criteria, shift, -1),
+                        KeyCode::Right => RangeField::ALL[row].adjust(&mut self.criteria, shift, 1),
+                        _ => return true,
+                    }
+                }
+                Focus::Table => match key.code {
+                    KeyCode::Up => self.table_state.scroll_up_by(1),
+                    KeyCode::Down => self.table_state.scroll_down_by(1),
+                    KeyCode::PageUp => self.table_state.scroll_up_by(20),
+                    KeyCode::PageDown => self.table_state.scroll_down_by(20),
+                    KeyCode::Home => self.table_state.select_first(),
+                    KeyCode::End => self.table_state.select_last(),
+                    KeyCode::Left => self.sort_cursor = self.sort_cursor.saturating_sub(1),
+                    KeyCode::Right => self.sort_cursor = (self.sort_cursor + 1).min(SortColumn::ALL.len() - 1),
+                    KeyCode::Char(c @ ('s' | 'S')) => {
+                        let column = SortColumn::ALL[self.sort_cursor];
+                        sort::toggle_sort(&mut self.criteria.sort, column, c == 'S');
+                    }
+                    _ => return true,
+                },
+            },
+        }
+        self.refresh();
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [search_area, types_area, main_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [type1_area, type2_area] = Layout::horizontal([Constraint::Fill(1); 2]).areas(types_area);
+        let [ranges_area, table_area] = Layout::horizontal([Constraint::Length(26), Constraint::Min(40)])
+            .areas(main_area);
+
+        let search = Paragraph::new(self.criteria.name.as_str())
+            .block(self.block("Search name", Focus::Search));
+        frame.render_widget(search, search_area);
+        if self.focus == Focus::Search {
+            let x = search_area.x + 1 + self.criteria.name.chars().count() as u16;
+            frame.set_cursor_position((x.min(search_area.right().saturating_sub(2)), search_area.y + 1));
+        }
+
+        for (area, focus, title, value) in [
+            (type1_area, Focus::Type1, "Type 1", self.criteria.type1),
+            (type2_area, Focus::Type2, "Type 2", self.criteria.type2),
+        ] {
+            let label = value.map_or("Any".to_string(), |t| t.to_string());
+            frame.render_widget(Paragraph::new(format!("◀ {} ▶", label)).block(self.block(title, focus)), area);
+        }
+
+        self.draw_ranges(frame, ranges_area);
+        self.draw_table(frame, table_area);
+
+        let help = match self.focus {
+            Focus::Search => "Type to search · Tab next · Ctrl+R reset · Esc quit",
+            Focus::Type1 | Focus::Type2 => "←/→ change type · Tab next · Ctrl+R reset · Esc quit",
+            Focus::Ranges => "↑/↓ pick · ←/→ min · Shift+←/→ max · Tab next · Esc quit",
+            Focus::Table => "↑/↓ scroll · ←/→ column · s sort · S add sort key · Tab next · Esc quit",
+        };
+        frame.render_widget(Paragraph::new(help).style(Style::new().fg(Color::DarkGray)), help_area);
+    }
+
+    fn draw_ranges(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = RangeField::ALL.iter()
+            .map(|field| {
+                let (min, max) = field.get(&self.criteria);
+                ListItem::new(format!("{:<7}{:>4} - {:<4}", field.label(), min, max))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.block("Ranges", Focus::Ranges))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        // Only show which row is picked while the panel has focus
+        let mut state = if self.focus == Focus::Ranges { self.range_state.clone() } else { ListState::default() };
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let sort_keys = &self.criteria.sort;
+        let header_cell = |label: &str, column: Option<SortColumn>| {
+            let mut text = label.to_string();
+            if let Some(i) = column.and_then(|c| sort_keys.iter().position(|k| k.column == c)) {
+                text.push_str(sort_keys[i].direction.arrow());
+                // Only number the keys once there is more than one
+                if sort_keys.len() > 1 {
+                    text.push_str(&(i + 1).to_string());
+                }
+            }
+            let highlighted = self.focus == Focus::Table && column == Some(SortColumn::ALL[self.sort_cursor]);
+            let style = if highlighted { Style::new().add_modifier(Modifier::REVERSED) } else { Style::new() };
+            Cell::from(text).style(style)
+        };
+        let header = Row::new([
+            header_cell("Name", Some(SortColumn::Name)),
+            header_cell("Type", None),
+            header_cell("HP", Some(SortColumn::Hp)),
+            header_cell("Atk", Some(SortColumn::Attack)),
+            header_cell("Def", Some(SortColumn::Defense)),
+            header_cell("SpA", Some(SortColumn::SpAtk)),
+            header_cell("SpD", Some(SortColumn::SpDef)),
+            header_cell("Spe", Some(SortColumn::Speed)),
+            header_cell("Total", Some(SortColumn::Total)),
+            header_cell("Height", Some(SortColumn::Height)),
+            header_cell("Weight", Some(SortColumn::Weight)),
+            header_cell("Gen", Some(SortColumn::Generation)),
+        ])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+
+        let rows = self.results.iter().map(|p| {
+            let types = match p.type2 {
+                Some(type2) => format!("{}/{}", p.type1, type2),
+                None => p.type1.to_string(),
+            };
+            Row::new([
+                p.display_name(),
+                types,
+                p.hp.to_string(),
+                p.attack.to_string(),
+                p.defense.to_string(),
+                p.sp_atk.to_string(),
+                p.sp_def.to_string(),
+                p.speed.to_string(),
+                p.total.to_string(),
+                format!("{} m", p.height),
+                format!("{} kg", p.weight),
+                p.generation.to_string(),
+            ])
+        });
+
+        let widths = [
+            Constraint::Min(16),
+            Constraint::Length(17),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(4),
+        ];
+        let title = format!("Found {} Pokémon", self.results.len());
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(self.block(&title, Focus::Table))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    // Bordered panel, highlighted while it has focus
+    fn block<'a>(&self, title: &'a str, focus: Focus) -> Block<'a> {
+        let style = if self.focus == focus { Style::new().fg(Color::Yellow) } else { Style::new() };
+        Block::bordered().title(Line::from(title)).border_style(style)
+    }
+}
+
+// Any -> Normal -> Fire -> ... -> Fairy -> Any
+fn cycle_type(current: Option<PokemonType>, forward: bool) -> Option<PokemonType> {
+    let options: Vec<Option<PokemonType>> = std::iter::once(None)
+        .chain(PokemonType::ALL.iter().copied().map(Some))
+        .collect();
+    let i = options.iter().position(|&t| t == current).unwrap_or(0);
+    let len = options.len();
+    options[if forward { (i + 1) % len } else { (i + len - 1) % len }]
+}
+
+fn run(mut terminal: DefaultTerminal, mut app: App) -> anyhow::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            // Windows also reports key releases
+            if key.kind == KeyEventKind::Press && !app.on_key(key) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut data = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            "--data" => data = Some(PathBuf::from(args.next().context("--data needs a path")?)),
+            _ => bail!("unexpected argument '{}'\n\n{}", arg, USAGE),
+        }
+    }
+
+    let pokedex = match &data {
+        Some(path) => {
+            let loaded = dataset::load_file(path)
+                .with_context(|| format!("Failed to load {}", path.display()))?;
+            // Printed before the UI takes over the screen, and still there after it exits
+            for warning in loaded.warnings() {
+                eprintln!("{}", warning);
+            }
+            loaded.pokemon
+        }
+        None => dataset::default_pokedex().context("Failed to load the built-in dataset")?,
+    };
+
+    // `init` puts the terminal back on panic as well, `restore` on a normal exit
+    let terminal = ratatui::init();
+    let result = run(terminal, App::new(pokedex));
+    ratatui::restore();
+    result
+}